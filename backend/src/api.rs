@@ -1,5 +1,13 @@
 pub use router::Router;
 
+mod admin;
 mod auth;
+mod body_limit;
+mod compression;
+mod bulk;
+mod health;
+mod metrics;
+mod page;
 mod protected;
+mod rate_limit;
 mod router;
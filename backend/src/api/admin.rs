@@ -0,0 +1,162 @@
+use axum::{extract::State, http::StatusCode, response::IntoResponse, routing::get, Json, Router};
+use serde::Serialize;
+
+use super::{page::Page, protected::internal_error};
+use crate::database::{Database, DatabaseModel, Repository, User};
+use crate::users::AdminUser;
+
+pub fn router(db: Database) -> Router<()> {
+    Router::new()
+        // /admin/users endpoint
+        .route("/admin/users", get(self::get::list_users))
+        .with_state(db)
+}
+
+#[derive(Debug, Serialize)]
+pub struct UserResponse {
+    pub id: i64,
+    pub username: String,
+    pub is_admin: bool,
+}
+
+impl From<&User> for UserResponse {
+    fn from(user: &User) -> Self {
+        Self {
+            id: *user.id(),
+            username: user.username.clone(),
+            is_admin: user.is_admin,
+        }
+    }
+}
+
+mod get {
+    use super::*;
+
+    pub async fn list_users(
+        AdminUser(admin): AdminUser,
+        State(db): State<Database>,
+    ) -> Result<impl IntoResponse, StatusCode> {
+        tracing::info!("Admin {} listed all users", admin.username);
+        let mut users = db
+            .users()
+            .map_err(internal_error)?
+            .list()
+            .map_err(internal_error)?;
+        users.sort_by(|a, b| a.username.cmp(&b.username));
+
+        Ok(Json(Page::complete(
+            users.iter().map(UserResponse::from).collect(),
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::users::{AuthSession, Backend};
+    use axum::{
+        body::Body,
+        extract::Path,
+        http::{header, Request},
+        routing::post,
+    };
+    use axum_login::AuthManagerLayerBuilder;
+    use serde_json::Value;
+    use tower::ServiceExt;
+    use tower_sessions::SessionManagerLayer;
+
+    fn setup() -> (tempfile::TempDir, Router) {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db = Database::open(temp_dir.path()).unwrap();
+        let users = db.users().unwrap();
+        let mut alice = users
+            .create_user("alice".to_string(), "password123".to_string())
+            .unwrap();
+        alice.is_admin = true;
+        users.update(&alice).unwrap();
+        users
+            .create_user("bob".to_string(), "password123".to_string())
+            .unwrap();
+
+        // Logs in without a password, standing in for the login form
+        let login_db = db.clone();
+        let login = post(
+            move |mut auth_session: AuthSession, Path(username): Path<String>| async move {
+                let users = login_db.users().unwrap();
+                let user = users.get_by_username(&username).unwrap().unwrap();
+                auth_session.login(&user).await.unwrap();
+            },
+        );
+        let session_layer = SessionManagerLayer::new(db.sessions().unwrap()).with_secure(false);
+        let auth_layer = AuthManagerLayerBuilder::new(Backend::new(db.clone()), session_layer).build();
+        let app = router(db.clone())
+            .route("/test/login/{username}", login)
+            .layer(auth_layer);
+
+        (temp_dir, app)
+    }
+
+    /// Log in as `username`, returning the session cookie
+    async fn login(app: &Router, username: &str) -> String {
+        let response = app
+            .clone()
+            .oneshot(
+                Request::post(format!("/test/login/{}", username))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let cookie = response.headers()[header::SET_COOKIE].to_str().unwrap();
+        cookie.split(';').next().unwrap().to_string()
+    }
+
+    async fn list_users(app: &Router, cookie: Option<&str>) -> (StatusCode, Value) {
+        let mut request = Request::get("/admin/users");
+        if let Some(cookie) = cookie {
+            request = request.header(header::COOKIE, cookie);
+        }
+        let response = app
+            .clone()
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+
+        (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+    }
+
+    #[tokio::test]
+    async fn test_admin_endpoints_require_a_login() {
+        let (_temp_dir, app) = setup();
+
+        let (status, _) = list_users(&app, None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_admin_endpoints_forbid_other_users() {
+        let (_temp_dir, app) = setup();
+        let cookie = login(&app, "bob").await;
+
+        let (status, _) = list_users(&app, Some(&cookie)).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_admins_can_list_users() {
+        let (_temp_dir, app) = setup();
+        let cookie = login(&app, "alice").await;
+
+        let (status, body) = list_users(&app, Some(&cookie)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["total"], 2);
+        assert_eq!(body["items"][0]["username"], "alice");
+        assert_eq!(body["items"][0]["is_admin"], true);
+        assert_eq!(body["items"][1]["username"], "bob");
+        assert_eq!(body["items"][1]["is_admin"], false);
+    }
+}
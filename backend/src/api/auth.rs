@@ -1,7 +1,9 @@
+use std::net::SocketAddr;
+
 use askama::Template;
 use axum::{
-    extract::Query,
-    http::StatusCode,
+    extract::{ConnectInfo, Query, State},
+    http::{header, StatusCode},
     response::{Html, IntoResponse, Redirect},
     routing::{get, post},
     Form, Router,
@@ -9,6 +11,7 @@ use axum::{
 use axum_messages::{Message, Messages};
 use serde::Deserialize;
 
+use crate::api::rate_limit::LoginRateLimiter;
 use crate::users::{AuthSession, Credentials};
 
 #[derive(Template)]
@@ -25,11 +28,12 @@ pub struct NextUrl {
     next: Option<String>,
 }
 
-pub fn router() -> Router<()> {
+pub fn router(rate_limiter: LoginRateLimiter) -> Router<()> {
     Router::new()
         .route("/login", post(self::post::login))
         .route("/login", get(self::get::login))
         .route("/logout", get(self::get::logout))
+        .with_state(rate_limiter)
 }
 
 mod post {
@@ -38,8 +42,20 @@ mod post {
     pub async fn login(
         mut auth_session: AuthSession,
         messages: Messages,
+        State(rate_limiter): State<LoginRateLimiter>,
+        ConnectInfo(addr): ConnectInfo<SocketAddr>,
         Form(creds): Form<Credentials>,
     ) -> impl IntoResponse {
+        // Throttle before verifying, since verification itself is the expensive part
+        let rate_limit_key = rate_limiter.key(addr.ip(), &creds.username);
+        if let Err(retry_after) = rate_limiter.check(rate_limit_key) {
+            return (
+                StatusCode::TOO_MANY_REQUESTS,
+                [(header::RETRY_AFTER, retry_after.as_secs().max(1).to_string())],
+            )
+                .into_response();
+        }
+
         let user = match auth_session.authenticate(creds.clone()).await {
             Ok(Some(user)) => user,
             Ok(None) => {
@@ -59,6 +75,8 @@ mod post {
             return StatusCode::INTERNAL_SERVER_ERROR.into_response();
         }
 
+        rate_limiter.reset(rate_limit_key);
+
         messages.success(format!("Successfully logged in as {}", user.username));
 
         if let Some(ref next) = creds.next {
@@ -0,0 +1,81 @@
+use axum::{
+    extract::DefaultBodyLimit,
+    http::StatusCode,
+    middleware,
+    response::{IntoResponse, Response},
+    Json, Router,
+};
+use serde_json::json;
+use tower_http::limit::RequestBodyLimitLayer;
+
+/// Reject requests with a body over `max_bytes` with `413 Payload Too Large`.
+///
+/// This replaces axum's default limit on extractors, so the configured limit
+/// applies however a handler reads the body, and both ways of exceeding it
+/// (a large `Content-Length` or a longer streamed body) get the same JSON error.
+pub fn limit_body_size(router: Router, max_bytes: usize) -> Router {
+    router
+        .layer(DefaultBodyLimit::disable())
+        .layer(RequestBodyLimitLayer::new(max_bytes))
+        .layer(middleware::map_response(payload_too_large_as_json))
+}
+
+async fn payload_too_large_as_json(response: Response) -> Response {
+    if response.status() != StatusCode::PAYLOAD_TOO_LARGE {
+        return response;
+    }
+
+    (
+        StatusCode::PAYLOAD_TOO_LARGE,
+        Json(json!({ "error": "Request body too large" })),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::Request, routing::post};
+    use tower::ServiceExt;
+
+    fn app() -> Router {
+        let router = Router::new().route(
+            "/echo",
+            post(|Json(body): Json<serde_json::Value>| async move { Json(body) }),
+        );
+        limit_body_size(router, 64)
+    }
+
+    fn request(body: String) -> Request<Body> {
+        Request::post("/echo")
+            .header("content-type", "application/json")
+            .body(Body::from(body))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_oversized_body_is_rejected() {
+        let body = json!({ "name": "x".repeat(100) }).to_string();
+
+        let response = app().oneshot(request(body)).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(&body).unwrap(),
+            json!({ "error": "Request body too large" })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_body_within_limit_is_accepted() {
+        let response = app()
+            .oneshot(request(json!({ "name": "x" }).to_string()))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
@@ -0,0 +1,127 @@
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+
+/// Envelope returned by endpoints that act on many items in one request.
+///
+/// Every input item gets an entry, in input order, carrying either the ID of
+/// what it produced or the status code and message describing why it failed.
+#[derive(Debug, Serialize)]
+pub struct BulkResponse<T> {
+    pub results: Vec<BulkItemResult<T>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BulkItemResult<T> {
+    /// Position of the item in the request
+    pub index: usize,
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<T>,
+    /// HTTP status code describing the failure of this item
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_code: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Why a single item of a bulk request failed
+#[derive(Debug)]
+pub struct BulkItemError {
+    pub status: StatusCode,
+    pub message: String,
+}
+
+impl BulkItemError {
+    pub fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+impl<T> BulkResponse<T> {
+    /// Build the envelope from per-item outcomes, given in request order
+    pub fn from_results(results: impl IntoIterator<Item = Result<T, BulkItemError>>) -> Self {
+        let results = results
+            .into_iter()
+            .enumerate()
+            .map(|(index, result)| match result {
+                Ok(id) => BulkItemResult {
+                    index,
+                    success: true,
+                    id: Some(id),
+                    error_code: None,
+                    error: None,
+                },
+                Err(err) => BulkItemResult {
+                    index,
+                    success: false,
+                    id: None,
+                    error_code: Some(err.status.as_u16()),
+                    error: Some(err.message),
+                },
+            })
+            .collect();
+
+        Self { results }
+    }
+
+    /// `200 OK` when every item succeeded, `207 Multi-Status` otherwise
+    pub fn status(&self) -> StatusCode {
+        if self.results.iter().all(|result| result.success) {
+            StatusCode::OK
+        } else {
+            StatusCode::MULTI_STATUS
+        }
+    }
+}
+
+impl<T: Serialize> IntoResponse for BulkResponse<T> {
+    fn into_response(self) -> Response {
+        (self.status(), Json(self)).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_mixed_batch_envelope() {
+        let response = BulkResponse::from_results(vec![
+            Ok(7),
+            Err(BulkItemError::new(StatusCode::CONFLICT, "Watcher already exists")),
+            Ok(8),
+        ]);
+
+        assert_eq!(response.status(), StatusCode::MULTI_STATUS);
+        assert_eq!(
+            serde_json::to_value(&response).unwrap(),
+            json!({
+                "results": [
+                    {"index": 0, "success": true, "id": 7},
+                    {
+                        "index": 1,
+                        "success": false,
+                        "error_code": 409,
+                        "error": "Watcher already exists"
+                    },
+                    {"index": 2, "success": true, "id": 8}
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn test_all_successful_batch_is_ok() {
+        let response = BulkResponse::<i64>::from_results(vec![Ok(1), Ok(2)]);
+
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
@@ -0,0 +1,60 @@
+use axum::Router;
+use tower_http::compression::{
+    predicate::{DefaultPredicate, Predicate, SizeAbove},
+    CompressionLayer,
+};
+
+/// Responses smaller than this aren't worth the overhead of compressing
+const MIN_COMPRESSED_SIZE: u16 = 1024;
+
+/// Compress responses with gzip, deflate or brotli when the client accepts one.
+///
+/// Small responses, images and event streams are sent as they are.
+pub fn compress_responses(router: Router) -> Router {
+    let predicate = DefaultPredicate::new().and(SizeAbove::new(MIN_COMPRESSED_SIZE));
+
+    router.layer(CompressionLayer::new().compress_when(predicate))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        body::Body,
+        http::{header, Request},
+        routing::get,
+    };
+    use tower::ServiceExt;
+
+    fn app() -> Router {
+        let router = Router::new()
+            .route("/large", get(|| async { "track ".repeat(1000) }))
+            .route("/small", get(|| async { "track" }));
+        compress_responses(router)
+    }
+
+    async fn content_encoding(uri: &str, accept_encoding: &str) -> Option<String> {
+        let request = Request::get(uri)
+            .header(header::ACCEPT_ENCODING, accept_encoding)
+            .body(Body::empty())
+            .unwrap();
+        let response = app().oneshot(request).await.unwrap();
+
+        response
+            .headers()
+            .get(header::CONTENT_ENCODING)
+            .map(|value| value.to_str().unwrap().to_string())
+    }
+
+    #[tokio::test]
+    async fn test_large_responses_are_compressed() {
+        assert_eq!(content_encoding("/large", "gzip").await.as_deref(), Some("gzip"));
+        assert_eq!(content_encoding("/large", "br").await.as_deref(), Some("br"));
+        assert_eq!(content_encoding("/large", "identity").await, None);
+    }
+
+    #[tokio::test]
+    async fn test_small_responses_are_not_compressed() {
+        assert_eq!(content_encoding("/small", "gzip").await, None);
+    }
+}
@@ -0,0 +1,59 @@
+use axum::{extract::State, http::StatusCode, response::IntoResponse, routing::get, Json, Router};
+use serde::Serialize;
+
+use crate::database::Database;
+
+pub fn router(db: Database) -> Router<()> {
+    Router::new()
+        .route("/health", get(self::get::health))
+        .route("/ready", get(self::get::ready))
+        .with_state(db)
+}
+
+#[derive(Debug, Serialize)]
+pub struct HealthResponse {
+    pub status: &'static str,
+    /// Names of the checks that failed, omitted when everything is fine
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub failed_checks: Vec<&'static str>,
+}
+
+mod get {
+    use super::*;
+
+    /// Liveness: the process is up and serving requests
+    pub async fn health() -> impl IntoResponse {
+        Json(HealthResponse {
+            status: "ok",
+            failed_checks: Vec::new(),
+        })
+    }
+
+    /// Readiness: the dependencies needed to serve requests are usable
+    pub async fn ready(State(db): State<Database>) -> impl IntoResponse {
+        let mut failed_checks = Vec::new();
+
+        if let Err(err) = db.ping() {
+            tracing::error!("Readiness check failed for database: {}", err);
+            failed_checks.push("database");
+        }
+
+        if failed_checks.is_empty() {
+            (
+                StatusCode::OK,
+                Json(HealthResponse {
+                    status: "ok",
+                    failed_checks,
+                }),
+            )
+        } else {
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                Json(HealthResponse {
+                    status: "unavailable",
+                    failed_checks,
+                }),
+            )
+        }
+    }
+}
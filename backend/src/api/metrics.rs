@@ -0,0 +1,17 @@
+use axum::{extract::State, routing::get, Router};
+use metrics_exporter_prometheus::PrometheusHandle;
+
+pub fn router(handle: PrometheusHandle) -> Router<()> {
+    Router::new()
+        .route("/metrics", get(self::get::metrics))
+        .with_state(handle)
+}
+
+mod get {
+    use super::*;
+
+    /// Current metrics in the Prometheus text format
+    pub async fn metrics(State(handle): State<PrometheusHandle>) -> String {
+        handle.render()
+    }
+}
@@ -0,0 +1,39 @@
+use serde::Serialize;
+
+/// Envelope returned by every list endpoint.
+///
+/// `next_cursor` is set when more items follow; pass it back to get the next
+/// page. `total` counts all matching items, when that is cheap to know.
+#[derive(Debug, Serialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub total: Option<i64>,
+    pub next_cursor: Option<String>,
+}
+
+impl<T> Page<T> {
+    /// A single page holding every matching item
+    pub fn complete(items: Vec<T>) -> Self {
+        Self {
+            total: Some(items.len() as i64),
+            items,
+            next_cursor: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_complete_page() {
+        let page = Page::complete(vec!["a", "b"]);
+
+        assert_eq!(
+            serde_json::to_value(&page).unwrap(),
+            json!({"items": ["a", "b"], "total": 2, "next_cursor": null})
+        );
+    }
+}
@@ -1,21 +1,36 @@
 use axum::{
-    extract::{Json, Path},
+    extract::{Json, Path, Query, State},
     http::StatusCode,
     response::IntoResponse,
     routing::{get, post},
     Router,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
 
-use crate::users::AuthSession;
+use super::page::Page;
+use crate::app::SupportedService;
+use crate::database::{Database, DatabaseModel, ModelError, Watcher, WatcherRepository};
+use crate::users::AuthenticatedUser;
 
-pub fn router() -> Router<()> {
+pub fn router(db: Database) -> Router<()> {
     Router::new()
+        // /services endpoint
+        .route("/services", get(get::list_services))
         // /watchers endpoints
         .route(
             "/watchers",
             get(get::list_watchers).post(post::create_watcher),
         )
+        // /watchers/batch endpoint
+        .route("/watchers/batch", post(post::create_watchers))
+        // /watchers/{watchername} endpoint
+        .route("/watchers/{watchername}", get(get::get_watcher))
+        // /watchers/{watchername}/archive endpoint
+        .route(
+            "/watchers/{watchername}/archive",
+            post(post::archive_watcher),
+        )
         // /watchers/{watchername}/ytmusic endpoints
         .route(
             "/watchers/{watchername}/ytmusic",
@@ -44,21 +59,104 @@ pub fn router() -> Router<()> {
         .route("/watchers/{watchername}/stop", get(get::stop_watcher))
         // /watchers/{watchername}/preview endpoint
         .route("/watchers/{watchername}/preview", get(get::preview_watcher))
+        .with_state(db)
+}
+
+#[derive(Debug, Serialize)]
+pub struct WatcherResponse {
+    pub id: u64,
+    pub name: String,
+    pub ytmusic_playlist: Option<String>,
+    pub spotify_playlist: Option<String>,
+    pub sync_frequency_secs: u64,
+    pub cron_expression: Option<String>,
+    pub active: bool,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub archived_at: Option<OffsetDateTime>,
+    pub market: Option<String>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub last_synced_at: Option<OffsetDateTime>,
+    /// When the next sync is due, `null` while inactive or before the first sync
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub next_sync_at: Option<OffsetDateTime>,
+}
+
+impl From<&Watcher> for WatcherResponse {
+    fn from(watcher: &Watcher) -> Self {
+        Self {
+            id: *watcher.id(),
+            name: watcher.name.clone(),
+            ytmusic_playlist: watcher.ytmusic_playlist.clone(),
+            spotify_playlist: watcher.spotify_playlist.clone(),
+            sync_frequency_secs: watcher.sync_frequency_secs,
+            cron_expression: watcher.cron_expression.clone(),
+            active: watcher.active,
+            archived_at: watcher.archived_at,
+            market: watcher.market.clone(),
+            last_synced_at: watcher.last_synced_at,
+            next_sync_at: watcher.next_sync_at(),
+        }
+    }
 }
 
 mod get {
     use super::*;
 
-    pub async fn list_watchers(auth_session: AuthSession) -> Result<impl IntoResponse, StatusCode> {
-        auth_required(auth_session)?;
-        Ok("List of watchers")
+    pub async fn list_services(_user: AuthenticatedUser) -> impl IntoResponse {
+        let services: Vec<_> = SupportedService::ALL
+            .iter()
+            .map(SupportedService::info)
+            .collect();
+        Json(Page::complete(services))
+    }
+
+    #[derive(Deserialize)]
+    pub struct ListWatchersQuery {
+        /// Only list watchers whose name contains this, ignoring case
+        pub q: Option<String>,
+        /// Also list archived watchers
+        #[serde(default)]
+        pub include_archived: bool,
+    }
+
+    pub async fn list_watchers(
+        AuthenticatedUser(user): AuthenticatedUser,
+        State(db): State<Database>,
+        Query(query): Query<ListWatchersQuery>,
+    ) -> Result<impl IntoResponse, StatusCode> {
+        let repo = db.watchers().map_err(internal_error)?;
+        let watchers = match query.q.as_deref().map(str::trim) {
+            Some(q) if !q.is_empty() => {
+                repo.search_for_owner(*user.id(), q, query.include_archived)
+            }
+            _ => repo.list_for_owner(*user.id(), query.include_archived),
+        }
+        .map_err(internal_error)?;
+
+        Ok(Json(Page::complete(
+            watchers.iter().map(WatcherResponse::from).collect(),
+        )))
+    }
+
+    pub async fn get_watcher(
+        AuthenticatedUser(user): AuthenticatedUser,
+        State(db): State<Database>,
+        Path(watchername): Path<String>,
+    ) -> Result<impl IntoResponse, StatusCode> {
+        let watcher = db
+            .watchers()
+            .map_err(internal_error)?
+            .get_by_name(*user.id(), &watchername)
+            .map_err(internal_error)?
+            .ok_or(StatusCode::NOT_FOUND)?;
+
+        Ok(Json(WatcherResponse::from(&watcher)))
     }
 
     pub async fn get_ytmusic(
-        auth_session: AuthSession,
+        _user: AuthenticatedUser,
         Path(watchername): Path<String>,
     ) -> Result<impl IntoResponse, StatusCode> {
-        auth_required(auth_session)?;
         Ok(format!(
             "Getting YouTube Music for watcher: {}",
             watchername
@@ -66,10 +164,9 @@ mod get {
     }
 
     pub async fn get_ytmusic_songs(
-        auth_session: AuthSession,
+        _user: AuthenticatedUser,
         Path(watchername): Path<String>,
     ) -> Result<impl IntoResponse, StatusCode> {
-        auth_required(auth_session)?;
         Ok(format!(
             "Getting YouTube Music songs for watcher: {}",
             watchername
@@ -77,18 +174,16 @@ mod get {
     }
 
     pub async fn get_spotify(
-        auth_session: AuthSession,
+        _user: AuthenticatedUser,
         Path(watchername): Path<String>,
     ) -> Result<impl IntoResponse, StatusCode> {
-        auth_required(auth_session)?;
         Ok(format!("Getting Spotify for watcher: {}", watchername))
     }
 
     pub async fn get_spotify_songs(
-        auth_session: AuthSession,
+        _user: AuthenticatedUser,
         Path(watchername): Path<String>,
     ) -> Result<impl IntoResponse, StatusCode> {
-        auth_required(auth_session)?;
         Ok(format!(
             "Getting Spotify songs for watcher: {}",
             watchername
@@ -96,47 +191,236 @@ mod get {
     }
 
     pub async fn start_watcher(
-        auth_session: AuthSession,
+        _user: AuthenticatedUser,
         Path(watchername): Path<String>,
     ) -> Result<impl IntoResponse, StatusCode> {
-        auth_required(auth_session)?;
         Ok(format!("Started watcher: {}", watchername))
     }
 
     pub async fn stop_watcher(
-        auth_session: AuthSession,
+        _user: AuthenticatedUser,
         Path(watchername): Path<String>,
     ) -> Result<impl IntoResponse, StatusCode> {
-        auth_required(auth_session)?;
         Ok(format!("Stopped watcher: {}", watchername))
     }
 
     pub async fn preview_watcher(
-        auth_session: AuthSession,
+        _user: AuthenticatedUser,
         Path(watchername): Path<String>,
     ) -> Result<impl IntoResponse, StatusCode> {
-        auth_required(auth_session)?;
         Ok(format!("Previewing watcher: {}", watchername))
     }
 }
 
 mod post {
     use super::*;
+    use crate::api::bulk::{BulkItemError, BulkResponse};
+
+    /// Largest number of watchers that can be created in one batch
+    const MAX_BATCH_SIZE: usize = 50;
+
+    #[derive(Deserialize)]
+    pub struct CreateWatcherRequest {
+        pub name: String,
+        pub sync_frequency_secs: Option<u64>,
+        pub cron_expression: Option<String>,
+        pub market: Option<String>,
+    }
+
+    /// A checked `CreateWatcherRequest`, ready to be applied to a new watcher
+    struct WatcherSettings {
+        name: String,
+        sync_frequency_secs: Option<u64>,
+        cron_expression: Option<String>,
+        market: Option<String>,
+    }
+
+    impl CreateWatcherRequest {
+        fn validate(&self) -> Result<WatcherSettings, BulkItemError> {
+            let invalid = |message| BulkItemError::new(StatusCode::UNPROCESSABLE_ENTITY, message);
+
+            let name = self.name.trim();
+            if name.is_empty() {
+                return Err(invalid("Name must not be empty"));
+            }
+            if self.sync_frequency_secs == Some(0) {
+                return Err(invalid("Sync frequency must be positive"));
+            }
+
+            let market = match self.market.as_deref() {
+                Some(market) => {
+                    Some(Watcher::normalize_market(market).ok_or_else(|| invalid("Invalid market"))?)
+                }
+                None => None,
+            };
+
+            let cron_expression = self.cron_expression.as_deref().map(str::trim);
+            if let Some(cron_expression) = cron_expression {
+                if Watcher::parse_cron_expression(cron_expression).is_err() {
+                    return Err(invalid("Invalid cron expression"));
+                }
+            }
+
+            Ok(WatcherSettings {
+                name: name.to_string(),
+                sync_frequency_secs: self.sync_frequency_secs,
+                cron_expression: cron_expression.map(str::to_string),
+                market,
+            })
+        }
+    }
+
+    impl WatcherSettings {
+        /// Apply the optional settings
+        fn apply(&self, watcher: &mut Watcher) {
+            if let Some(sync_frequency_secs) = self.sync_frequency_secs {
+                watcher.sync_frequency_secs = sync_frequency_secs;
+            }
+            if let Some(cron_expression) = &self.cron_expression {
+                watcher.cron_expression = Some(cron_expression.clone());
+            }
+            if let Some(market) = &self.market {
+                watcher.market = Some(market.clone());
+            }
+        }
+    }
 
     pub async fn create_watcher(
-        auth_session: AuthSession,
-        body: String,
+        AuthenticatedUser(user): AuthenticatedUser,
+        State(db): State<Database>,
+        Json(request): Json<CreateWatcherRequest>,
+    ) -> Result<impl IntoResponse, StatusCode> {
+        let settings = request.validate().map_err(|err| err.status)?;
+
+        let watchers = db.watchers().map_err(internal_error)?;
+        let watcher = match watchers.create_watcher(*user.id(), settings.name.clone(), |watcher| {
+            settings.apply(watcher)
+        }) {
+            Ok(watcher) => watcher,
+            Err(ModelError::InvalidData(_)) => return Err(StatusCode::CONFLICT),
+            Err(err) => return Err(internal_error(err)),
+        };
+
+        Ok((StatusCode::CREATED, Json(WatcherResponse::from(&watcher))))
+    }
+
+    /// Create several watchers at once, or none if any of them is invalid.
+    ///
+    /// Every item gets a result; when some items fail, the valid ones are
+    /// reported as `424 Failed Dependency` since nothing was created.
+    pub async fn create_watchers(
+        AuthenticatedUser(user): AuthenticatedUser,
+        State(db): State<Database>,
+        Json(requests): Json<Vec<CreateWatcherRequest>>,
+    ) -> Result<impl IntoResponse, StatusCode> {
+        if requests.is_empty() || requests.len() > MAX_BATCH_SIZE {
+            return Err(StatusCode::UNPROCESSABLE_ENTITY);
+        }
+
+        let watchers = db.watchers().map_err(internal_error)?;
+        let results = check_batch(&watchers, *user.id(), &requests)?;
+        if results.iter().any(Result::is_err) {
+            return Ok(reject_batch(results));
+        }
+
+        let settings: Vec<_> = results.into_iter().flatten().collect();
+        let names = settings.iter().map(|settings| settings.name.clone()).collect();
+        match watchers.create_watchers(*user.id(), names, |index, watcher| {
+            settings[index].apply(watcher);
+        }) {
+            Ok(created) => Ok(BulkResponse::from_results(
+                created.iter().map(|watcher| Ok(*watcher.id())),
+            )),
+            // A watcher with one of the names was created since the check, so
+            // check again to tell which item it was
+            Err(ModelError::InvalidData(_)) => Ok(reject_batch(check_batch(
+                &watchers,
+                *user.id(),
+                &requests,
+            )?)),
+            Err(err) => Err(internal_error(err)),
+        }
+    }
+
+    /// Validate every item of a batch, including that its name is free
+    fn check_batch(
+        watchers: &WatcherRepository,
+        owner_id: i64,
+        requests: &[CreateWatcherRequest],
+    ) -> Result<Vec<Result<WatcherSettings, BulkItemError>>, StatusCode> {
+        let mut results: Vec<Result<WatcherSettings, BulkItemError>> =
+            Vec::with_capacity(requests.len());
+
+        for request in requests {
+            let settings = match request.validate() {
+                Ok(settings) => settings,
+                Err(err) => {
+                    results.push(Err(err));
+                    continue;
+                }
+            };
+
+            let repeated = results.iter().flatten().any(|earlier| earlier.name == settings.name);
+            let taken = watchers
+                .get_by_name(owner_id, &settings.name)
+                .map_err(internal_error)?
+                .is_some();
+            results.push(if repeated {
+                Err(BulkItemError::new(
+                    StatusCode::CONFLICT,
+                    "Watcher name repeated in batch",
+                ))
+            } else if taken {
+                Err(BulkItemError::new(
+                    StatusCode::CONFLICT,
+                    "Watcher already exists",
+                ))
+            } else {
+                Ok(settings)
+            });
+        }
+
+        Ok(results)
+    }
+
+    /// Results for a batch of which nothing was created
+    fn reject_batch(results: Vec<Result<WatcherSettings, BulkItemError>>) -> BulkResponse<u64> {
+        // Every item passing the check again means the conflicting watcher has
+        // been removed in the meantime
+        let (status, message) = if results.iter().any(Result::is_err) {
+            (StatusCode::FAILED_DEPENDENCY, "Not created because other items failed")
+        } else {
+            (StatusCode::CONFLICT, "Conflicted with a concurrent change, try again")
+        };
+
+        BulkResponse::from_results(results.into_iter().map(|result| {
+            result.and_then(|_| Err(BulkItemError::new(status, message)))
+        }))
+    }
+
+    pub async fn archive_watcher(
+        AuthenticatedUser(user): AuthenticatedUser,
+        State(db): State<Database>,
+        Path(watchername): Path<String>,
     ) -> Result<impl IntoResponse, StatusCode> {
-        auth_required(auth_session)?;
-        Ok(format!("Created watcher with name: {}", body))
+        let watchers = db.watchers().map_err(internal_error)?;
+        let watcher = watchers
+            .get_by_name(*user.id(), &watchername)
+            .map_err(internal_error)?
+            .ok_or(StatusCode::NOT_FOUND)?;
+
+        let watcher = watchers
+            .archive_watcher(watcher.id())
+            .map_err(internal_error)?;
+
+        Ok(Json(WatcherResponse::from(&watcher)))
     }
 
     pub async fn post_ytmusic(
-        auth_session: AuthSession,
+        _user: AuthenticatedUser,
         Path(watchername): Path<String>,
         body: String,
     ) -> Result<impl IntoResponse, StatusCode> {
-        auth_required(auth_session)?;
         Ok(format!(
             "Posted to YouTube Music for watcher: {} with data: {}",
             watchername, body
@@ -149,11 +433,10 @@ mod post {
     }
 
     pub async fn post_spotify(
-        auth_session: AuthSession,
+        _user: AuthenticatedUser,
         Path(watchername): Path<String>,
         Json(data): Json<SpotifyData>,
     ) -> Result<impl IntoResponse, StatusCode> {
-        auth_required(auth_session)?;
         Ok(format!(
             "Posted to Spotify for watcher: {} with playlist: {}",
             watchername, data.playlist
@@ -161,11 +444,10 @@ mod post {
     }
 
     pub async fn share_watcher(
-        auth_session: AuthSession,
+        _user: AuthenticatedUser,
         Path(watchername): Path<String>,
         body: String,
     ) -> Result<impl IntoResponse, StatusCode> {
-        auth_required(auth_session)?;
         Ok(format!(
             "Shared watcher: {} with user: {}",
             watchername, body
@@ -173,10 +455,127 @@ mod post {
     }
 }
 
-// Shared helper function for authentication
-fn auth_required(auth_session: AuthSession) -> Result<(), StatusCode> {
-    match auth_session.user {
-        Some(_) => Ok(()),
-        None => Err(StatusCode::UNAUTHORIZED),
+// Shared helper to log unexpected errors and hide them from the client
+pub(super) fn internal_error(err: impl std::fmt::Display) -> StatusCode {
+    tracing::error!("Internal error: {}", err);
+    StatusCode::INTERNAL_SERVER_ERROR
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::Repository;
+    use serde_json::{json, Value};
+
+    async fn create_watchers(db: &Database, batch: Value) -> (StatusCode, Value) {
+        let user = db
+            .users()
+            .unwrap()
+            .get_by_username("ferris")
+            .unwrap()
+            .unwrap();
+        let requests = serde_json::from_value(batch).unwrap();
+
+        let response = post::create_watchers(AuthenticatedUser(user), State(db.clone()), Json(requests))
+            .await
+            .into_response();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+
+        (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+    }
+
+    fn setup() -> (tempfile::TempDir, Database) {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db = Database::open(temp_dir.path()).unwrap();
+        db.users()
+            .unwrap()
+            .create_user("ferris".to_string(), "hunter42".to_string())
+            .unwrap();
+        (temp_dir, db)
+    }
+
+    fn watcher_count(db: &Database) -> usize {
+        db.watchers().unwrap().list().unwrap().len()
+    }
+
+    #[tokio::test]
+    async fn test_create_watcher_stores_its_settings() {
+        let (_temp_dir, db) = setup();
+        let user = db.users().unwrap().get_by_username("ferris").unwrap().unwrap();
+        let create = |request: Value| {
+            let request = serde_json::from_value(request).unwrap();
+            post::create_watcher(AuthenticatedUser(user.clone()), State(db.clone()), Json(request))
+        };
+
+        let response = create(json!({"name": "gym", "sync_frequency_secs": 120, "market": "de"}))
+            .await
+            .into_response();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let stored = db.watchers().unwrap().get_by_name(*user.id(), "gym").unwrap().unwrap();
+        assert_eq!(stored.sync_frequency_secs, 120);
+        assert_eq!(stored.market.as_deref(), Some("DE"));
+
+        let response = create(json!({"name": "gym"})).await.into_response();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        assert_eq!(watcher_count(&db), 1);
+    }
+
+    #[tokio::test]
+    async fn test_batch_creates_all_watchers() {
+        let (_temp_dir, db) = setup();
+
+        let (status, body) =
+            create_watchers(&db, json!([{"name": "gym"}, {"name": "road trip", "market": "de"}])).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["results"][1]["success"], true);
+        assert_eq!(watcher_count(&db), 2);
+    }
+
+    #[tokio::test]
+    async fn test_batch_size_is_capped() {
+        let (_temp_dir, db) = setup();
+        let batch: Vec<_> = (0..51).map(|i| json!({"name": format!("watcher {}", i)})).collect();
+
+        let (status, _) = create_watchers(&db, Value::Array(batch)).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        let (status, _) = create_watchers(&db, json!([])).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+        assert_eq!(watcher_count(&db), 0);
+    }
+
+    #[tokio::test]
+    async fn test_batch_rejects_repeated_names() {
+        let (_temp_dir, db) = setup();
+
+        let (status, body) =
+            create_watchers(&db, json!([{"name": "gym"}, {"name": " gym "}])).await;
+
+        assert_eq!(status, StatusCode::MULTI_STATUS);
+        assert_eq!(body["results"][0]["error_code"], 424);
+        assert_eq!(body["results"][1]["error_code"], 409);
+        assert_eq!(watcher_count(&db), 0);
+    }
+
+    #[tokio::test]
+    async fn test_batch_is_all_or_nothing() {
+        let (_temp_dir, db) = setup();
+        create_watchers(&db, json!([{"name": "gym"}])).await;
+
+        let (status, body) = create_watchers(
+            &db,
+            json!([{"name": "road trip"}, {"name": "gym"}, {"name": ""}]),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::MULTI_STATUS);
+        assert_eq!(body["results"][0]["error_code"], 424);
+        assert_eq!(body["results"][1]["error_code"], 409);
+        assert_eq!(body["results"][2]["error_code"], 422);
+        assert_eq!(watcher_count(&db), 1);
     }
 }
@@ -0,0 +1,225 @@
+use std::{
+    collections::{hash_map::RandomState, HashMap, VecDeque},
+    hash::BuildHasher,
+    net::IpAddr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use crate::config::AppConfig;
+use crate::database::User;
+
+// Most keys tracked at once. Past this, attempts at usernames that aren't tracked
+// yet are counted per client address alone.
+const MAX_TRACKED_KEYS: usize = 10_000;
+
+/// A fixed-window limiter for login attempts.
+///
+/// Password verification is deliberately expensive, so attempts are counted per
+/// key (client address and username, ignoring its case) before the credentials
+/// are checked at all.
+#[derive(Clone)]
+pub struct LoginRateLimiter {
+    max_attempts: u32,
+    window: Duration,
+    hasher: RandomState,
+    attempts: Arc<Mutex<Attempts>>,
+}
+
+/// What attempts are counted by: a client address, and the username tried
+/// unless too many keys are tracked already.
+///
+/// Usernames are hashed, so keys have the same size however long the username sent.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AttemptKey {
+    ip: IpAddr,
+    username: Option<u64>,
+}
+
+struct AttemptWindow {
+    started: Instant,
+    count: u32,
+}
+
+#[derive(Default)]
+struct Attempts {
+    windows: HashMap<AttemptKey, AttemptWindow>,
+    // Keys in the order their windows started, so expired and oldest windows are
+    // found without scanning. Entries whose window was removed since are skipped.
+    order: VecDeque<(AttemptKey, Instant)>,
+}
+
+impl Attempts {
+    fn is_current(&self, key: &AttemptKey, started: Instant) -> bool {
+        self.windows
+            .get(key)
+            .is_some_and(|window| window.started == started)
+    }
+
+    /// Drop the windows that started at least `window` ago
+    fn prune(&mut self, now: Instant, window: Duration) {
+        while let Some(&(key, started)) = self.order.front() {
+            if now.duration_since(started) < window {
+                break;
+            }
+            self.order.pop_front();
+            if self.is_current(&key, started) {
+                self.windows.remove(&key);
+            }
+        }
+    }
+
+    /// Drop the window that started first
+    fn evict_oldest(&mut self) {
+        while let Some((key, started)) = self.order.pop_front() {
+            if self.is_current(&key, started) {
+                self.windows.remove(&key);
+                return;
+            }
+        }
+    }
+}
+
+impl LoginRateLimiter {
+    pub fn new(max_attempts: u32, window: Duration) -> Self {
+        Self {
+            max_attempts,
+            window,
+            hasher: RandomState::new(),
+            attempts: Arc::new(Mutex::new(Attempts::default())),
+        }
+    }
+
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self::new(
+            config.login_rate_limit,
+            Duration::from_secs(config.login_rate_limit_window_secs),
+        )
+    }
+
+    /// The key counting attempts from `ip` at logging in as `username`.
+    ///
+    /// Usernames are keyed the way the user index keys them, so trying other
+    /// casings of a name doesn't get a fresh set of attempts.
+    pub fn key(&self, ip: IpAddr, username: &str) -> AttemptKey {
+        AttemptKey {
+            ip,
+            username: Some(self.hasher.hash_one(User::username_key(username))),
+        }
+    }
+
+    /// Record an attempt for `key`.
+    ///
+    /// Returns how long the caller has to wait if the key is over its limit.
+    pub fn check(&self, key: AttemptKey) -> Result<(), Duration> {
+        let now = Instant::now();
+        let mut attempts = self.attempts.lock().unwrap();
+        attempts.prune(now, self.window);
+
+        let mut key = key;
+        if !attempts.windows.contains_key(&key) && attempts.windows.len() >= MAX_TRACKED_KEYS {
+            // Someone is cycling through usernames; stop giving each one its own
+            // attempts, and make room for the address if it isn't tracked yet
+            key.username = None;
+            if !attempts.windows.contains_key(&key) {
+                attempts.evict_oldest();
+            }
+        }
+
+        let Attempts { windows, order } = &mut *attempts;
+        let window = windows.entry(key).or_insert_with(|| {
+            order.push_back((key, now));
+            AttemptWindow {
+                started: now,
+                count: 0,
+            }
+        });
+
+        if window.count >= self.max_attempts {
+            return Err(self.window - now.duration_since(window.started));
+        }
+
+        window.count += 1;
+        Ok(())
+    }
+
+    /// Forget all attempts recorded for `key`, e.g. after a successful login.
+    pub fn reset(&self, key: AttemptKey) {
+        self.attempts.lock().unwrap().windows.remove(&key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOCALHOST: IpAddr = IpAddr::V4(std::net::Ipv4Addr::LOCALHOST);
+
+    #[test]
+    fn test_rejects_attempts_over_limit() {
+        let limiter = LoginRateLimiter::new(10, Duration::from_secs(60));
+        let key = limiter.key(LOCALHOST, "ferris");
+
+        for _ in 0..10 {
+            assert!(limiter.check(key).is_ok());
+        }
+
+        let retry_after = limiter.check(key).unwrap_err();
+        assert!(retry_after <= Duration::from_secs(60));
+        assert!(retry_after > Duration::from_secs(0));
+    }
+
+    #[test]
+    fn test_limits_are_per_key() {
+        let limiter = LoginRateLimiter::new(1, Duration::from_secs(60));
+
+        assert!(limiter.check(limiter.key(LOCALHOST, "ferris")).is_ok());
+        assert!(limiter.check(limiter.key(LOCALHOST, "ferris")).is_err());
+        assert!(limiter.check(limiter.key(LOCALHOST, "crab")).is_ok());
+        assert!(limiter.check(limiter.key([10, 0, 0, 1].into(), "ferris")).is_ok());
+    }
+
+    #[test]
+    fn test_other_casings_share_a_key() {
+        let limiter = LoginRateLimiter::new(1, Duration::from_secs(60));
+
+        assert!(limiter.check(limiter.key(LOCALHOST, "ferris")).is_ok());
+        assert!(limiter.check(limiter.key(LOCALHOST, "Ferris")).is_err());
+        assert!(limiter.check(limiter.key(LOCALHOST, "FERRIS")).is_err());
+    }
+
+    #[test]
+    fn test_window_expiry_and_reset() {
+        let limiter = LoginRateLimiter::new(1, Duration::from_millis(20));
+        let key = limiter.key(LOCALHOST, "ferris");
+
+        assert!(limiter.check(key).is_ok());
+        assert!(limiter.check(key).is_err());
+
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(limiter.check(key).is_ok());
+        assert_eq!(limiter.attempts.lock().unwrap().windows.len(), 1);
+
+        limiter.reset(key);
+        assert!(limiter.check(key).is_ok());
+    }
+
+    #[test]
+    fn test_tracked_keys_are_capped() {
+        let limiter = LoginRateLimiter::new(1, Duration::from_secs(60));
+        for i in 0..MAX_TRACKED_KEYS {
+            let username = format!("user{}", i);
+            assert!(limiter.check(limiter.key(LOCALHOST, &username)).is_ok());
+        }
+
+        // New usernames share the attempts of their address, which takes the
+        // place of the oldest key
+        assert!(limiter.check(limiter.key(LOCALHOST, "crab")).is_ok());
+        assert!(limiter.check(limiter.key(LOCALHOST, "lobster")).is_err());
+        assert!(limiter.check(limiter.key(LOCALHOST, "user0")).is_err());
+        assert_eq!(limiter.attempts.lock().unwrap().windows.len(), MAX_TRACKED_KEYS);
+
+        // Keys tracked already keep their own attempts
+        assert!(limiter.check(limiter.key(LOCALHOST, "user1")).is_err());
+    }
+}
@@ -4,12 +4,13 @@ use axum_login::{
     AuthManagerLayerBuilder,
 };
 use axum_messages::MessagesManagerLayer;
+use std::net::SocketAddr;
 use time::Duration;
 use tokio::{signal, task::AbortHandle};
-use tower_sessions::cookie::Key;
+use tower_sessions::{cookie::Key, session_store::ExpiredDeletion};
 
 use crate::{
-    api::{auth, protected},
+    api::{admin, auth, body_limit, compression, health, metrics, protected, rate_limit::LoginRateLimiter},
     app::Watcher,
     config::AppConfig,
     database::Database,
@@ -33,7 +34,7 @@ impl Router {
         //
         // This uses `tower-sessions` to establish a layer that will provide the session
         // as a request extension.
-        let session_store = self.db.repository("sessions")?;
+        let session_store = self.db.sessions()?;
 
         let deletion_task = tokio::task::spawn(
             session_store
@@ -55,15 +56,25 @@ impl Router {
         // service which will provide the auth session as a request extension.
         let backend = Backend::new(self.db.clone());
         // Initialize backend with test user
-        backend.initialize().await?;
+        backend.initialize(&self.config.admin_usernames()).await?;
 
         let auth_layer = AuthManagerLayerBuilder::new(backend, session_layer).build();
 
-        let app = protected::router()
+        let app = protected::router(self.db.clone())
+            .merge(admin::router(self.db.clone()))
             .route_layer(login_required!(Backend, login_url = "/login"))
-            .merge(auth::router())
+            .merge(auth::router(LoginRateLimiter::from_config(&self.config)))
+            .merge(health::router(self.db.clone()))
+            .merge(metrics::router(crate::metrics::install()?))
             .layer(MessagesManagerLayer)
             .layer(auth_layer.clone());
+        let app = body_limit::limit_body_size(app, self.config.max_request_body_bytes);
+        // A proxy in front of the app may already compress responses
+        let app = if self.config.compression {
+            compression::compress_responses(app)
+        } else {
+            app
+        };
 
         let bind_address = self.config.bind_address();
         let listener = tokio::net::TcpListener::bind(&bind_address)
@@ -71,11 +82,20 @@ impl Router {
             .unwrap_or_else(|_| panic!("Failed to bind to {}", bind_address));
 
         // Ensure we use a shutdown signal to abort the deletion task.
-        axum::serve(listener, app.into_make_service())
-            .with_graceful_shutdown(shutdown_signal(deletion_task.abort_handle()))
-            .await?;
-
-        deletion_task.await??;
+        // Connection info is needed to key login rate limiting by client address.
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .with_graceful_shutdown(shutdown_signal(deletion_task.abort_handle()))
+        .await?;
+
+        // The deletion task was aborted by the shutdown signal, which is expected
+        match deletion_task.await {
+            Ok(result) => result?,
+            Err(err) if err.is_cancelled() => {}
+            Err(err) => return Err(err.into()),
+        }
 
         Ok(())
     }
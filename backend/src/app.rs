@@ -1,4 +1,7 @@
+pub use service::SupportedService;
 pub use watcher::Watcher;
 
+mod matching;
+mod service;
 mod watcher;
 mod songlink;
\ No newline at end of file
@@ -0,0 +1,132 @@
+#![allow(dead_code)] // Will be used by track matching during sync
+use std::collections::HashSet;
+
+/// Words marking a bracketed or dashed title suffix that doesn't change the recording
+const TITLE_NOISE: &[&str] = &[
+    "remaster",
+    "remastered",
+    "feat",
+    "ft",
+    "featuring",
+    "with",
+    "mono",
+    "stereo",
+    "explicit",
+    "deluxe",
+    "bonus",
+];
+
+/// Words marking a suffix that names a different recording, which always wins
+const TITLE_VERSION: &[&str] = &["live", "remix", "mix", "acoustic", "instrumental", "demo"];
+
+/// Normalize a track title for comparison.
+///
+/// Lowercases, drops suffixes like "(Remastered 2011)", "[feat. X]" or
+/// " - 2011 Remaster", and reduces punctuation to single spaces. Suffixes that
+/// name a different recording, like "(Live)" or "(Remix)", are kept.
+pub fn normalize_title(title: &str) -> String {
+    let mut title = title.to_lowercase();
+
+    // Bracketed suffixes
+    while let Some(start) = title.rfind(['(', '[']) {
+        let close = if title[start..].starts_with('(') { ')' } else { ']' };
+        let end = title[start..]
+            .find(close)
+            .map_or(title.len(), |end| start + end + 1);
+
+        if !is_noise(&title[start..end]) {
+            break;
+        }
+        title.replace_range(start..end, " ");
+    }
+
+    // Dashed suffixes, as used by Spotify: "Song - Remastered 2011"
+    if let Some(start) = title.rfind(" - ") {
+        if is_noise(&title[start..]) {
+            title.truncate(start);
+        }
+    }
+
+    normalize_words(&title)
+}
+
+/// Normalize an artist credit for comparison.
+///
+/// Lowercases, spells out "&", unifies "ft." and "featuring" as "feat" and
+/// reduces punctuation to single spaces, so "A & B" and "A and B" compare equal.
+pub fn normalize_artist(artist: &str) -> String {
+    normalize_words(&artist.to_lowercase().replace('&', " and "))
+        .split(' ')
+        .map(|word| match word {
+            "ft" | "featuring" => "feat",
+            word => word,
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// How alike two normalized strings are, from 0.0 to 1.0.
+///
+/// Compares the sets of words in both, ignoring order and repetition, so
+/// "b and a" is as similar to "a and b" as "a and b" is.
+pub fn similarity(a: &str, b: &str) -> f32 {
+    let a: HashSet<_> = a.split_whitespace().collect();
+    let b: HashSet<_> = b.split_whitespace().collect();
+
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+
+    let shared = a.intersection(&b).count();
+    2.0 * shared as f32 / (a.len() + b.len()) as f32
+}
+
+fn is_noise(suffix: &str) -> bool {
+    let suffix = normalize_words(suffix);
+    let mut words = suffix.split(' ');
+    words.clone().any(|word| TITLE_NOISE.contains(&word))
+        && !words.any(|word| TITLE_VERSION.contains(&word))
+}
+
+fn normalize_words(s: &str) -> String {
+    // Apostrophes join words rather than separating them: "don't" is "dont"
+    s.replace(['\'', '\u{2019}'], "")
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_title() {
+        assert_eq!(normalize_title("Song (Remastered 2011)"), "song");
+        assert_eq!(normalize_title("Song - 2011 Remaster"), "song");
+        assert_eq!(normalize_title("Song [feat. Someone] (Mono)"), "song");
+        assert_eq!(normalize_title("Song (Live)"), "song live");
+        assert_eq!(normalize_title("Song - Live with Orchestra"), "song live with orchestra");
+        assert_eq!(normalize_title("Don't Stop - Live at Wembley"), "dont stop live at wembley");
+    }
+
+    #[test]
+    fn test_normalize_artist() {
+        assert_eq!(normalize_artist("A & B"), normalize_artist("A and B"));
+        assert_eq!(normalize_artist("A ft. B"), normalize_artist("A feat. B"));
+        assert_eq!(normalize_artist("A featuring B"), "a feat b");
+    }
+
+    #[test]
+    fn test_similarity() {
+        let remastered = normalize_title("Song (Remastered 2011)");
+        assert_eq!(similarity(&remastered, &normalize_title("Song")), 1.0);
+        assert_eq!(
+            similarity(&normalize_artist("A & B"), &normalize_artist("B and A")),
+            1.0
+        );
+        assert!(similarity("song live", "song") < 1.0);
+        assert_eq!(similarity("song", "other"), 0.0);
+    }
+}
@@ -0,0 +1,157 @@
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::songlink::Platform;
+
+/// A streaming service playlists can be synced between
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SupportedService {
+    YoutubeMusic,
+    Spotify,
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("Unsupported service: {0}")]
+pub struct UnsupportedService(String);
+
+impl SupportedService {
+    pub const ALL: [SupportedService; 2] = [SupportedService::YoutubeMusic, SupportedService::Spotify];
+
+    /// The canonical name used in the API and in storage
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SupportedService::YoutubeMusic => "youtube_music",
+            SupportedService::Spotify => "spotify",
+        }
+    }
+
+    /// The platform Songlink reports links to this service under
+    pub fn songlink_platform(&self) -> Platform {
+        match self {
+            SupportedService::YoutubeMusic => Platform::YoutubeMusic,
+            SupportedService::Spotify => Platform::Spotify,
+        }
+    }
+
+    /// Whether sync can read tracks from this service
+    pub fn can_be_source(&self) -> bool {
+        // No sync path reads from either service yet
+        match self {
+            SupportedService::YoutubeMusic => false,
+            SupportedService::Spotify => false,
+        }
+    }
+
+    /// Whether sync can add tracks to this service
+    pub fn can_be_target(&self) -> bool {
+        // No sync path writes to either service yet
+        match self {
+            SupportedService::YoutubeMusic => false,
+            SupportedService::Spotify => false,
+        }
+    }
+
+    /// What the service can be used for, as shown to clients
+    pub fn info(&self) -> ServiceInfo {
+        let display_name = match self {
+            SupportedService::YoutubeMusic => "YouTube Music",
+            SupportedService::Spotify => "Spotify",
+        };
+
+        ServiceInfo {
+            service: *self,
+            display_name,
+            songlink_platform: self.songlink_platform(),
+            can_be_source: self.can_be_source(),
+            can_be_target: self.can_be_target(),
+        }
+    }
+}
+
+/// A supported service and its capabilities
+#[derive(Debug, Serialize)]
+pub struct ServiceInfo {
+    pub service: SupportedService,
+    pub display_name: &'static str,
+    pub songlink_platform: Platform,
+    /// Whether a watcher can read tracks from this service
+    pub can_be_source: bool,
+    /// Whether a watcher can add tracks to this service
+    pub can_be_target: bool,
+}
+
+impl fmt::Display for SupportedService {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for SupportedService {
+    type Err = UnsupportedService;
+
+    /// Accepts any casing and separators, so "YouTube Music", "youtube-music"
+    /// and "youtube_music" all name the same service.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized: String = s
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric())
+            .map(|c| c.to_ascii_lowercase())
+            .collect();
+
+        match normalized.as_str() {
+            "youtubemusic" | "ytmusic" => Ok(SupportedService::YoutubeMusic),
+            "spotify" => Ok(SupportedService::Spotify),
+            _ => Err(UnsupportedService(s.to_string())),
+        }
+    }
+}
+
+impl Serialize for SupportedService {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for SupportedService {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_variant_spellings_normalize() {
+        for name in ["youtube_music", "YouTube Music", "youtube-music", "YTMusic"] {
+            assert_eq!(
+                name.parse::<SupportedService>().unwrap(),
+                SupportedService::YoutubeMusic
+            );
+        }
+        assert_eq!(
+            " Spotify ".parse::<SupportedService>().unwrap(),
+            SupportedService::Spotify
+        );
+        assert!("deezer".parse::<SupportedService>().is_err());
+    }
+
+    #[test]
+    fn test_serializes_canonical_name() {
+        let service: SupportedService = serde_json::from_str("\"YouTube Music\"").unwrap();
+
+        assert_eq!(serde_json::to_string(&service).unwrap(), "\"youtube_music\"");
+    }
+
+    #[test]
+    fn test_service_info_uses_songlink_platform_keys() {
+        let info = serde_json::to_value(SupportedService::YoutubeMusic.info()).unwrap();
+
+        assert_eq!(info["service"], "youtube_music");
+        assert_eq!(info["songlink_platform"], "youtubeMusic");
+        assert_eq!(info["can_be_target"], false);
+    }
+}
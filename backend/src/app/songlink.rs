@@ -1,12 +1,21 @@
 // src/songlink_api.rs
 #![allow(dead_code)]
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
 
 use anyhow::{Context, Result};
-use reqwest::Client;
-use serde::{Deserialize, Deserializer};
+use reqwest::{Client, RequestBuilder};
+use serde::{Deserialize, Deserializer, Serialize};
 use url::Url;
 
+const DEFAULT_BASE_URL: &str = "https://api.song.link/v1-alpha.1";
+const FALLBACK_COUNTRY: &str = "US";
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+// Query parameters whose values must never show up in errors or logs
+const SECRET_PARAMS: &[&str] = &["key"];
+
 /// A wrapper around `Url` that trims extraneous angle brackets before parsing.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct UrlWrapper(pub Url);
@@ -30,24 +39,61 @@ pub struct SonglinkClient {
     base_url: String,
     // Optionally, you can store an API key if provided.
     api_key: Option<String>,
+    // Country used when a request doesn't ask for one
+    default_country: Option<String>,
 }
 
 impl SonglinkClient {
-    /// Create a new SonglinkClient. `api_key` is optional.
+    /// Create a new SonglinkClient for the public API. `api_key` is optional.
     pub fn new(api_key: Option<String>) -> Self {
+        Self::with_config(DEFAULT_BASE_URL.to_string(), api_key)
+    }
+
+    /// Create a new SonglinkClient talking to `base_url`, e.g. a mirror or a mock server.
+    pub fn with_config(base_url: String, api_key: Option<String>) -> Self {
         SonglinkClient {
-            client: Client::new(),
-            base_url: "https://api.song.link/v1-alpha.1".to_string(),
+            client: Self::http_client(DEFAULT_TIMEOUT),
+            base_url: base_url.trim_end_matches('/').to_string(),
             api_key,
+            default_country: None,
         }
     }
 
+    /// Give up on requests that take longer than `timeout`, connecting included.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.client = Self::http_client(timeout);
+        self
+    }
+
+    fn http_client(timeout: Duration) -> Client {
+        Client::builder()
+            .connect_timeout(timeout)
+            .timeout(timeout)
+            .build()
+            .expect("Failed to build HTTP client")
+    }
+
+    /// Use `country` for requests that don't pass their own `user_country`.
+    pub fn with_default_country(mut self, country: Option<String>) -> Self {
+        self.default_country = country;
+        self
+    }
+
+    /// The country to look up links in: the requested one, then the client's
+    /// default, then "US".
+    fn resolve_country<'a>(&'a self, user_country: Option<&'a str>) -> &'a str {
+        user_country
+            .or(self.default_country.as_deref())
+            .unwrap_or(FALLBACK_COUNTRY)
+    }
+
     /// Fetch links for a given song URL.
     ///
     /// # Arguments
     ///
     /// * `song_url` - A URL of a song or album from a supported platform.
-    /// * `user_country` - Optional two-letter country code, defaults to "US" if None.
+    /// * `user_country` - Optional two-letter country code, e.g. the watcher's market.
+    ///   Falls back to the client's default country, then "US".
     /// * `song_if_single` - Optional flag to enable improved matching for singles.
     ///
     /// # Example
@@ -69,9 +115,7 @@ impl SonglinkClient {
         // Build the request with query parameters.
         let mut req = self.client.get(&url).query(&[("url", song_url)]);
 
-        // Use provided user country or default to "US"
-        let country = user_country.unwrap_or("US");
-        req = req.query(&[("userCountry", country)]);
+        req = req.query(&[("userCountry", self.resolve_country(user_country))]);
 
         // Optionally set songIfSingle flag.
         if let Some(flag) = song_if_single {
@@ -83,27 +127,80 @@ impl SonglinkClient {
             req = req.query(&[("key", key)]);
         }
 
+        let started = Instant::now();
+        let result = Self::send(req).await;
+
+        let outcome = match &result {
+            Ok(_) => "ok",
+            Err(err) if is_timeout(err) => "timeout",
+            Err(_) => "error",
+        };
+        crate::metrics::record_songlink_request(outcome, started.elapsed());
+
+        result
+    }
+
+    async fn send(req: RequestBuilder) -> Result<LinksResponse> {
         // Send the request and handle errors.
+        // reqwest errors carry the request URL, which includes the API key.
         let resp = req
             .send()
             .await
+            .map_err(redact_error)
             .context("Failed to send request to Songlink API")?;
 
         // Check for HTTP errors.
         let resp = resp
             .error_for_status()
+            .map_err(redact_error)
             .context("Songlink API returned an error")?;
 
         // Deserialize the response.
         let links_response = resp
             .json::<LinksResponse>()
             .await
+            .map_err(redact_error)
             .context("Failed to deserialize Songlink API response")?;
 
         Ok(links_response)
     }
 }
 
+/// Replace the values of secret query parameters in the URL of an error
+fn redact_error(mut err: reqwest::Error) -> reqwest::Error {
+    if let Some(url) = err.url_mut() {
+        redact_url(url);
+    }
+    err
+}
+
+fn redact_url(url: &mut Url) {
+    if !url.query_pairs().any(|(name, _)| SECRET_PARAMS.contains(&name.as_ref())) {
+        return;
+    }
+
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .map(|(name, value)| {
+            let value = if SECRET_PARAMS.contains(&name.as_ref()) {
+                "[redacted]".to_string()
+            } else {
+                value.into_owned()
+            };
+            (name.into_owned(), value)
+        })
+        .collect();
+    url.query_pairs_mut().clear().extend_pairs(pairs);
+}
+
+/// Whether `fetch_links` failed because the Songlink API didn't answer in time.
+///
+/// Timeouts are worth retrying, unlike an error response for a track Songlink can't find.
+pub fn is_timeout(err: &anyhow::Error) -> bool {
+    err.downcast_ref::<reqwest::Error>()
+        .is_some_and(|err| err.is_timeout())
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct LinksResponse {
@@ -127,6 +224,34 @@ pub struct LinksResponse {
     pub entities_by_unique_id: HashMap<String, Entity>,
 }
 
+/// A Songlink match that is an album where a single track was expected
+#[derive(Debug, thiserror::Error)]
+#[error("Songlink matched an album, not a track: {0}")]
+pub struct NotATrack(pub String);
+
+impl LinksResponse {
+    /// The ID of the matching track on `platform`, or `None` if it isn't available there.
+    ///
+    /// Albums are rejected instead of having their ID used as a track ID, since
+    /// adding one to a playlist would first require expanding it into its tracks.
+    pub fn track_id(&self, platform: &Platform) -> Result<Option<&str>, NotATrack> {
+        let Some(entity) = self
+            .links_by_platform
+            .get(platform)
+            .and_then(|link| self.entities_by_unique_id.get(&link.entity_unique_id))
+        else {
+            return Ok(None);
+        };
+
+        match entity.entity_type {
+            EntityType::Song => Ok(Some(&entity.id)),
+            EntityType::Album => Err(NotATrack(
+                entity.title.clone().unwrap_or_else(|| entity.id.clone()),
+            )),
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Link {
@@ -181,7 +306,7 @@ pub enum EntityType {
     Album,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Eq, Hash)]
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Hash)]
 #[serde(rename_all = "camelCase")]
 pub enum Platform {
     Spotify,
@@ -231,7 +356,6 @@ pub enum APIProvider {
 mod tests {
     use super::*;
     use httpmock::{Method::GET, MockServer};
-    
 
     #[tokio::test]
     async fn test_fetch_links_success() {
@@ -257,11 +381,7 @@ mod tests {
             .await;
 
         // Instantiate the client with the mock server URL.
-        let client = SonglinkClient {
-            client: Client::new(),
-            base_url: server.url(""),
-            api_key: None,
-        };
+        let client = SonglinkClient::with_config(server.url(""), None);
 
         // Call fetch_links with the dummy song URL.
         let result = client
@@ -281,6 +401,21 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_track_id_rejects_albums() {
+        let mut json: serde_json::Value =
+            serde_json::from_str(include_str!("example_response.json")).unwrap();
+        json["entitiesByUniqueId"]["SPOTIFY_SONG::0Jcij1eWd5bDMU5iPbxe2i"]["type"] = "album".into();
+        let response: LinksResponse = serde_json::from_value(json).unwrap();
+
+        assert!(response.track_id(&Platform::Spotify).is_err());
+        assert_eq!(
+            response.track_id(&Platform::YoutubeMusic).unwrap(),
+            Some("w3LJ2bDvDJs")
+        );
+        assert_eq!(response.track_id(&Platform::Boomplay).unwrap(), None);
+    }
+
     #[tokio::test]
     async fn test_fetch_links_http_error() {
         let server = MockServer::start_async().await;
@@ -295,11 +430,7 @@ mod tests {
             })
             .await;
 
-        let client = SonglinkClient {
-            client: Client::new(),
-            base_url: server.url(""),
-            api_key: None,
-        };
+        let client = SonglinkClient::with_config(server.url(""), None);
 
         // Call fetch_links with a URL that triggers an error.
         let result = client.fetch_links("bad_url", None, None).await;
@@ -311,6 +442,55 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_fetch_links_error_redacts_api_key() {
+        let server = MockServer::start_async().await;
+
+        server
+            .mock_async(|when, then| {
+                when.method(GET).path("/links");
+                then.status(500);
+            })
+            .await;
+
+        let client = SonglinkClient::with_config(server.url(""), Some("secret-key".to_string()));
+
+        let err = client.fetch_links("test_song_url", None, None).await.unwrap_err();
+        let logged = format!("{:#} {:?}", err, err);
+        assert!(logged.contains("test_song_url"));
+        assert!(logged.contains("key=%5Bredacted%5D"));
+        assert!(!logged.contains("secret-key"));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_links_timeout() {
+        let server = MockServer::start_async().await;
+
+        server
+            .mock_async(|when, then| {
+                when.method(GET).path("/links");
+                then.status(200).delay(Duration::from_secs(2));
+            })
+            .await;
+
+        let client = SonglinkClient::with_config(server.url(""), None)
+            .with_timeout(Duration::from_millis(100));
+
+        let err = client.fetch_links("slow_url", None, None).await.unwrap_err();
+        assert!(is_timeout(&err));
+    }
+
+    #[test]
+    fn test_country_precedence() {
+        let client = SonglinkClient::new(None);
+        assert_eq!(client.resolve_country(None), "US");
+        assert_eq!(client.resolve_country(Some("FR")), "FR");
+
+        let client = client.with_default_country(Some("DE".to_string()));
+        assert_eq!(client.resolve_country(None), "DE");
+        assert_eq!(client.resolve_country(Some("FR")), "FR");
+    }
+
     #[tokio::test]
     #[ignore]
     async fn test_fetch_links_live_success() {
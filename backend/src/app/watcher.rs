@@ -1,5 +1,8 @@
+use std::time::Duration;
+
 use crate::app::songlink::SonglinkClient;
 use crate::config::AppConfig;
+use crate::database::Watcher as WatcherModel;
 
 pub struct Watcher {
     #[allow(dead_code)] // Will be used in future implementations
@@ -8,8 +11,23 @@ pub struct Watcher {
 
 impl Watcher {
     pub async fn new(config: &AppConfig) -> Result<Self, Box<dyn std::error::Error>> {
-        Ok(Self {
-            songlink_client: SonglinkClient::new(config.songlink_api_key.clone()),
-        })
+        let api_key = config.songlink_api_key.clone();
+        let songlink_client = match &config.songlink_base_url {
+            Some(base_url) => SonglinkClient::with_config(base_url.clone(), api_key),
+            None => SonglinkClient::new(api_key),
+        };
+
+        let default_market = match &config.default_market {
+            Some(market) => Some(
+                WatcherModel::normalize_market(market)
+                    .ok_or_else(|| format!("Invalid default market: {}", market))?,
+            ),
+            None => None,
+        };
+        let songlink_client = songlink_client
+            .with_default_country(default_market)
+            .with_timeout(Duration::from_secs(config.songlink_timeout_secs));
+
+        Ok(Self { songlink_client })
     }
 }
@@ -6,6 +6,10 @@ use std::path::Path;
 const DEFAULT_DB_PATH: &str = "./db.sled";
 const DEFAULT_HOST: &str = "0.0.0.0";
 const DEFAULT_PORT: u16 = 3000;
+const DEFAULT_LOGIN_RATE_LIMIT: u32 = 10;
+const DEFAULT_LOGIN_RATE_LIMIT_WINDOW_SECS: u64 = 60;
+const DEFAULT_SONGLINK_TIMEOUT_SECS: u64 = 10;
+const DEFAULT_MAX_REQUEST_BODY_BYTES: usize = 1024 * 1024;
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct AppConfig {
@@ -23,10 +27,39 @@ pub struct AppConfig {
     
     /// Songlink API key (optional)
     pub songlink_api_key: Option<String>,
+
+    /// Songlink API base URL (optional, defaults to the public API)
+    pub songlink_base_url: Option<String>,
+
+    /// Seconds before a Songlink API request is given up on
+    #[serde(default = "default_songlink_timeout_secs")]
+    pub songlink_timeout_secs: u64,
+
+    /// Market used for watchers that don't set their own (optional, defaults to US)
+    pub default_market: Option<String>,
     
     /// Log level (default from RUST_LOG env or fallback to info)
     #[serde(default = "default_log_level")]
     pub log_level: String,
+
+    /// Maximum login attempts per client and username within one window
+    #[serde(default = "default_login_rate_limit")]
+    pub login_rate_limit: u32,
+
+    /// Length of the login rate limiting window in seconds
+    #[serde(default = "default_login_rate_limit_window_secs")]
+    pub login_rate_limit_window_secs: u64,
+
+    /// Largest request body accepted, in bytes
+    #[serde(default = "default_max_request_body_bytes")]
+    pub max_request_body_bytes: usize,
+
+    /// Compress responses for clients that accept it
+    #[serde(default = "default_compression")]
+    pub compression: bool,
+
+    /// Comma-separated usernames promoted to admin on startup (optional)
+    pub admin_usernames: Option<String>,
 }
 
 fn default_db_path() -> String {
@@ -45,11 +78,37 @@ fn default_log_level() -> String {
     "axum_login=debug,tower_sessions=debug,tower_http=debug".to_string()
 }
 
+fn default_login_rate_limit() -> u32 {
+    DEFAULT_LOGIN_RATE_LIMIT
+}
+
+fn default_login_rate_limit_window_secs() -> u64 {
+    DEFAULT_LOGIN_RATE_LIMIT_WINDOW_SECS
+}
+
+fn default_songlink_timeout_secs() -> u64 {
+    DEFAULT_SONGLINK_TIMEOUT_SECS
+}
+
+fn default_compression() -> bool {
+    true
+}
+
+fn default_max_request_body_bytes() -> usize {
+    DEFAULT_MAX_REQUEST_BODY_BYTES
+}
+
 impl AppConfig {
     pub fn load() -> Result<Self, ConfigError> {
         // Try to load .env file, but don't fail if it doesn't exist
         let _ = dotenv::dotenv();
-        
+
+        // Add in settings from the environment
+        // APP_DB_PATH, APP_HOST, APP_PORT, etc.
+        Self::from_env(Environment::with_prefix("APP"))
+    }
+
+    fn from_env(env: Environment) -> Result<Self, ConfigError> {
         // Load from multiple sources in order, with later sources overriding earlier ones
         let config = Config::builder()
             // Start with defaults
@@ -57,11 +116,14 @@ impl AppConfig {
             .set_default("host", DEFAULT_HOST)?
             .set_default("port", DEFAULT_PORT)?
             .set_default("log_level", default_log_level())?
+            .set_default("login_rate_limit", DEFAULT_LOGIN_RATE_LIMIT)?
+            .set_default("login_rate_limit_window_secs", DEFAULT_LOGIN_RATE_LIMIT_WINDOW_SECS)?
+            .set_default("songlink_timeout_secs", DEFAULT_SONGLINK_TIMEOUT_SECS)?
+            .set_default("max_request_body_bytes", DEFAULT_MAX_REQUEST_BODY_BYTES as u64)?
+            .set_default("compression", default_compression())?
             // Add in settings from the config file if it exists
             .add_source(File::with_name("config").required(false))
-            // Add in settings from the environment
-            // APP_DB_PATH, APP_HOST, APP_PORT, etc.
-            .add_source(Environment::with_prefix("APP").separator("_"))
+            .add_source(env)
             // Build the config
             .build()?;
             
@@ -69,6 +131,16 @@ impl AppConfig {
         config.try_deserialize()
     }
     
+    /// Usernames to promote to admin, from the comma-separated setting
+    pub fn admin_usernames(&self) -> Vec<&str> {
+        self.admin_usernames
+            .iter()
+            .flat_map(|usernames| usernames.split(','))
+            .map(str::trim)
+            .filter(|username| !username.is_empty())
+            .collect()
+    }
+
     /// Get the bind address string (host:port)
     pub fn bind_address(&self) -> String {
         format!("{}:{}", self.host, self.port)
@@ -87,4 +159,62 @@ impl AppConfig {
         
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn config_from(vars: &[(&str, &str)]) -> Result<AppConfig, ConfigError> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+        AppConfig::from_env(Environment::with_prefix("APP").source(Some(vars)))
+    }
+
+    #[test]
+    fn test_defaults() {
+        let config = config_from(&[]).unwrap();
+
+        assert_eq!(config.db_path, DEFAULT_DB_PATH);
+        assert_eq!(config.login_rate_limit, DEFAULT_LOGIN_RATE_LIMIT);
+        assert_eq!(
+            config.login_rate_limit_window_secs,
+            DEFAULT_LOGIN_RATE_LIMIT_WINDOW_SECS
+        );
+        assert!(config.songlink_base_url.is_none());
+        assert_eq!(config.songlink_timeout_secs, DEFAULT_SONGLINK_TIMEOUT_SECS);
+        assert_eq!(config.max_request_body_bytes, DEFAULT_MAX_REQUEST_BODY_BYTES);
+        assert!(config.admin_usernames().is_empty());
+        assert!(config.compression);
+    }
+
+    #[test]
+    fn test_env_overrides() {
+        let config = config_from(&[
+            ("APP_DB_PATH", "/tmp/test.sled"),
+            ("APP_LOGIN_RATE_LIMIT", "3"),
+            ("APP_LOGIN_RATE_LIMIT_WINDOW_SECS", "30"),
+            ("APP_SONGLINK_BASE_URL", "http://localhost:8080"),
+            ("APP_DEFAULT_MARKET", "DE"),
+            ("APP_MAX_REQUEST_BODY_BYTES", "4096"),
+            ("APP_ADMIN_USERNAMES", "ferris, alice,"),
+            ("APP_COMPRESSION", "false"),
+        ])
+        .unwrap();
+
+        assert_eq!(config.db_path, "/tmp/test.sled");
+        assert_eq!(config.login_rate_limit, 3);
+        assert_eq!(config.login_rate_limit_window_secs, 30);
+        assert_eq!(
+            config.songlink_base_url.as_deref(),
+            Some("http://localhost:8080")
+        );
+        assert_eq!(config.default_market.as_deref(), Some("DE"));
+        assert_eq!(config.max_request_body_bytes, 4096);
+        assert_eq!(config.admin_usernames(), vec!["ferris", "alice"]);
+        assert!(!config.compression);
+    }
+}
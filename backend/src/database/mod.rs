@@ -6,37 +6,66 @@ use anyhow::Result;
 use std::path::Path;
 use thiserror::Error;
 
-pub use models::User;
-pub use sled::{SledRepository, UserRepository};
-pub use traits::{CreatableModel, DatabaseModel, ModelError, Repository, UpdatableModel};
+pub use models::{User, Watcher};
+pub use sled::{
+    SchedulerLeases, SledRepository, SledSessionStore, UserRepository, WatcherRepository,
+};
+pub use traits::{DatabaseModel, ModelError, Repository};
+
+/// Key read by readiness checks; it doesn't need to exist
+const PING_KEY: &[u8] = b"ping";
 
 #[derive(Debug, Error)]
 pub enum DatabaseError {
     #[error("Sled error: {0}")]
-    SledError(#[from] sled::Error),
+    SledError(#[from] ::sled::Error),
 
     #[error("Model error: {0}")]
     ModelError(#[from] ModelError),
 }
 
 /// A wrapper around the Sled database that provides access to repositories
+#[derive(Debug, Clone)]
 pub struct Database {
-    db: sled::Db,
+    db: ::sled::Db,
 }
 
 impl Database {
     /// Open a new database at the specified path
     pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, DatabaseError> {
-        let db = sled::open(path)?;
+        let db = ::sled::open(path)?;
         Ok(Self { db })
     }
 
+    /// Check that the database is still usable with a read that changes nothing
+    pub fn ping(&self) -> Result<(), DatabaseError> {
+        self.db.get(PING_KEY)?;
+        Ok(())
+    }
+
     /// Get a user repository
     pub fn users(&self) -> Result<UserRepository, DatabaseError> {
         Ok(UserRepository::new(self.db.clone())?)
     }
 
+    /// Get a watcher repository
+    pub fn watchers(&self) -> Result<WatcherRepository, DatabaseError> {
+        Ok(WatcherRepository::new(self.db.clone())?)
+    }
+
+    /// Get the session store used by `tower-sessions`
+    pub fn sessions(&self) -> Result<SledSessionStore, DatabaseError> {
+        Ok(SledSessionStore::new(self.db.clone())?)
+    }
+
+    /// Get the leases that keep schedulers from syncing the same watcher
+    #[allow(dead_code)] // Will be used by the sync scheduler
+    pub fn leases(&self) -> Result<SchedulerLeases, DatabaseError> {
+        Ok(SchedulerLeases::new(self.db.clone())?)
+    }
+
     /// Get a generic repository for a model type
+    #[allow(dead_code)] // Will be used in future implementations
     pub fn repository<T: DatabaseModel>(&self, tree_name: &str) -> Result<SledRepository<T>, DatabaseError> {
         Ok(SledRepository::new(self.db.clone(), tree_name)?)
     }
@@ -50,4 +79,4 @@ impl From<DatabaseError> for tower_sessions::session_store::Error {
 }
 
 #[cfg(test)]
-mod tests; 
\ No newline at end of file
+mod tests;
@@ -1,12 +1,19 @@
-use super::traits::{CreatableModel, DatabaseModel, ModelError, UpdatableModel};
+use crate::database::traits::{
+    CreatableModel, DatabaseModel, IndexedModel, ModelError, UpdatableModel,
+};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
+/// Index of users by their username, ignoring case
+pub const USERNAME_INDEX: &str = "username";
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct User {
     id: i64,
     pub username: String,
     password: String,
+    /// Whether the user may use privileged endpoints
+    pub is_admin: bool,
 }
 
 // Implement Debug manually to avoid logging the password hash
@@ -16,6 +23,7 @@ impl fmt::Debug for User {
             .field("id", &self.id)
             .field("username", &self.username)
             .field("password", &"[redacted]")
+            .field("is_admin", &self.is_admin)
             .finish()
     }
 }
@@ -28,12 +36,23 @@ impl DatabaseModel for User {
     }
 }
 
+impl IndexedModel for User {
+    fn index_keys(&self) -> Vec<(&'static str, Vec<u8>)> {
+        vec![(USERNAME_INDEX, Self::username_key(&self.username))]
+    }
+
+    fn unique_keys(&self) -> Vec<(&'static str, Vec<u8>)> {
+        vec![(USERNAME_INDEX, Self::username_key(&self.username))]
+    }
+}
+
 impl CreatableModel for User {
     fn new(id: Self::Id) -> Self {
         Self {
             id,
             username: String::new(),
             password: String::new(),
+            is_admin: false,
         }
     }
 }
@@ -42,6 +61,7 @@ impl UpdatableModel for User {
     fn update(&mut self, other: &Self) {
         self.username = other.username.clone();
         self.password = other.password.clone();
+        self.is_admin = other.is_admin;
     }
 }
 
@@ -52,6 +72,7 @@ impl User {
             id,
             username,
             password,
+            is_admin: false,
         }
     }
 
@@ -65,8 +86,23 @@ impl User {
         &self.password
     }
 
+    /// Key of a username in the username index, the same for every casing of it
+    pub fn username_key(username: &str) -> Vec<u8> {
+        username.to_lowercase().into_bytes()
+    }
+
     /// Verify if the given password matches the stored hash
     pub fn verify_password(&self, password: &str) -> Result<bool, ModelError> {
         Ok(password_auth::verify_password(password, &self.password).is_ok())
     }
+
+    /// Check if the password hash was made with weaker parameters than new hashes use
+    pub fn password_hash_is_obsolete(&self) -> bool {
+        password_auth::is_hash_obsolete(&self.password).unwrap_or(false)
+    }
+
+    /// Replace the password, hashing it with the current parameters
+    pub fn set_password(&mut self, password: &str) {
+        self.password = password_auth::generate_hash(password);
+    }
 } 
\ No newline at end of file
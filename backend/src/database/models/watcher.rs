@@ -0,0 +1,123 @@
+use crate::database::traits::{DatabaseModel, IndexedModel};
+use croner::{errors::CronError, Cron};
+use serde::{Deserialize, Serialize};
+use time::{Duration, OffsetDateTime};
+
+/// How often a watcher syncs unless configured otherwise
+const DEFAULT_SYNC_FREQUENCY_SECS: u64 = 60 * 60;
+
+/// Index of watchers by the user owning them
+pub const OWNER_INDEX: &str = "owner";
+/// Index of watchers by owner and name
+pub const OWNER_NAME_INDEX: &str = "owner_name";
+
+/// A watcher links a YouTube Music playlist with a Spotify playlist for one user
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Watcher {
+    id: u64,
+    pub owner_id: i64,
+    pub name: String,
+    pub ytmusic_playlist: Option<String>,
+    pub spotify_playlist: Option<String>,
+    /// Seconds between two syncs
+    pub sync_frequency_secs: u64,
+    /// Cron expression (UTC) for when to sync, used instead of the frequency when set
+    pub cron_expression: Option<String>,
+    pub active: bool,
+    pub last_synced_at: Option<OffsetDateTime>,
+    /// When the watcher was archived; archived watchers never sync again
+    pub archived_at: Option<OffsetDateTime>,
+    /// ISO 3166-1 alpha-2 country whose track availability is used when matching,
+    /// overriding the configured default market
+    pub market: Option<String>,
+}
+
+impl DatabaseModel for Watcher {
+    type Id = u64;
+
+    fn id(&self) -> &Self::Id {
+        &self.id
+    }
+}
+
+impl IndexedModel for Watcher {
+    fn index_keys(&self) -> Vec<(&'static str, Vec<u8>)> {
+        vec![
+            (OWNER_INDEX, self.owner_id.to_be_bytes().to_vec()),
+            (OWNER_NAME_INDEX, Self::owner_name_key(self.owner_id, &self.name)),
+        ]
+    }
+
+    fn unique_keys(&self) -> Vec<(&'static str, Vec<u8>)> {
+        vec![(OWNER_NAME_INDEX, Self::owner_name_key(self.owner_id, &self.name))]
+    }
+}
+
+impl Watcher {
+    /// Create a new, inactive watcher owned by the given user
+    pub fn with_owner(id: u64, owner_id: i64, name: String) -> Self {
+        Self {
+            id,
+            owner_id,
+            name,
+            ytmusic_playlist: None,
+            spotify_playlist: None,
+            sync_frequency_secs: DEFAULT_SYNC_FREQUENCY_SECS,
+            cron_expression: None,
+            active: false,
+            last_synced_at: None,
+            archived_at: None,
+            market: None,
+        }
+    }
+
+    /// When the next sync is due.
+    ///
+    /// `None` if the watcher is inactive, archived or hasn't completed its first sync yet,
+    /// in which case it runs as soon as the watcher is picked up.
+    pub fn next_sync_at(&self) -> Option<OffsetDateTime> {
+        if !self.active || self.archived_at.is_some() {
+            return None;
+        }
+
+        let last_synced_at = self.last_synced_at?;
+        match &self.cron_expression {
+            Some(expression) => next_cron_run(expression, last_synced_at),
+            None => {
+                let frequency =
+                    Duration::seconds(self.sync_frequency_secs.try_into().unwrap_or(i64::MAX));
+                last_synced_at.checked_add(frequency)
+            }
+        }
+    }
+
+    /// Parse a cron expression of five fields, or six with leading seconds
+    pub fn parse_cron_expression(expression: &str) -> Result<Cron, CronError> {
+        Cron::new(expression).with_seconds_optional().parse()
+    }
+
+    /// Canonical form of a market code, `None` if it isn't two ASCII letters
+    pub fn normalize_market(market: &str) -> Option<String> {
+        let market = market.trim();
+        if market.len() == 2 && market.chars().all(|c| c.is_ascii_alphabetic()) {
+            Some(market.to_ascii_uppercase())
+        } else {
+            None
+        }
+    }
+
+    /// Key of a watcher in the owner/name index
+    pub fn owner_name_key(owner_id: i64, name: &str) -> Vec<u8> {
+        let mut key = owner_id.to_be_bytes().to_vec();
+        key.extend_from_slice(name.as_bytes());
+        key
+    }
+}
+
+/// The first time after `after` matching a cron expression, in UTC
+fn next_cron_run(expression: &str, after: OffsetDateTime) -> Option<OffsetDateTime> {
+    let cron = Watcher::parse_cron_expression(expression).ok()?;
+    let after = chrono::DateTime::from_timestamp(after.unix_timestamp(), after.nanosecond())?;
+    let next = cron.find_next_occurrence(&after, false).ok()?;
+    OffsetDateTime::from_unix_timestamp(next.timestamp()).ok()
+}
@@ -0,0 +1,173 @@
+use super::SledRepository;
+use crate::database::traits::{IndexedModel, ModelError, Repository};
+use sled::{
+    transaction::{ConflictableTransactionError, TransactionError},
+    Batch, Db, Transactional, Tree,
+};
+
+/// A repository that keeps secondary indexes next to its models.
+///
+/// All indexes of a repository live in one companion tree, keyed by
+/// `index name | index key | model ID`. Models and their index entries are
+/// written in a single transaction, so an interrupted write can't leave
+/// entries pointing at models that don't exist.
+///
+/// Unique keys are kept in a second tree, keyed by `index name | index key`
+/// alone, so that the transaction can check whether a key is taken.
+pub struct IndexedRepository<T: IndexedModel> {
+    base_repo: SledRepository<T>,
+    index_tree: Tree,
+    unique_tree: Tree,
+}
+
+impl<T: IndexedModel> IndexedRepository<T> {
+    /// Create a new repository with the given database and tree name
+    pub fn new(db: Db, tree_name: &str) -> Result<Self, ModelError> {
+        Ok(Self {
+            base_repo: SledRepository::new(db.clone(), tree_name)?,
+            index_tree: db.open_tree(format!("{}_indexes", tree_name))?,
+            unique_tree: db.open_tree(format!("{}_unique", tree_name))?,
+        })
+    }
+
+    /// Find all models whose index `index_name` holds `key`
+    pub fn find_by_index(&self, index_name: &str, key: &[u8]) -> Result<Vec<T>, ModelError> {
+        let tree = self.base_repo.tree()?;
+        let mut models = Vec::new();
+
+        for entry in self.index_tree.scan_prefix(index_prefix(index_name, key)) {
+            let (_, model_key) = entry?;
+            if let Some(bytes) = tree.get(model_key)? {
+                models.push(self.base_repo.deserialize(&bytes)?);
+            }
+        }
+
+        Ok(models)
+    }
+
+    /// Insert several models in one transaction, so either all or none are stored.
+    ///
+    /// Fails with `ModelError::InvalidData` if any of them shares a unique key
+    /// with a stored model or with another one of them.
+    pub fn insert_all(&self, models: &[T]) -> Result<(), ModelError> {
+        let writes: Vec<_> = models.iter().map(|model| (model.id(), Some(model))).collect();
+        self.write(&writes)
+    }
+
+    /// Replace the models stored under the given IDs (or remove them where the
+    /// model is `None`) together with their index entries
+    fn write(&self, writes: &[(&T::Id, Option<&T>)]) -> Result<(), ModelError> {
+        let tree = self.base_repo.tree()?;
+        let writes = writes
+            .iter()
+            .map(|(id, model)| {
+                let key = self.base_repo.serialize(id)?;
+                let value = model.map(|model| self.base_repo.serialize(model)).transpose()?;
+                let new_entries = model
+                    .map(|model| index_entries(model, &key))
+                    .unwrap_or_default();
+                let new_unique = model.map(unique_entries).unwrap_or_default();
+                Ok((key, value, new_entries, new_unique))
+            })
+            .collect::<Result<Vec<_>, ModelError>>()?;
+
+        (&tree, &self.index_tree, &self.unique_tree)
+            .transaction(|(tree, index_tree, unique_tree)| {
+                for (key, value, new_entries, new_unique) in &writes {
+                    let mut batch = Batch::default();
+
+                    if let Some(bytes) = tree.get(key)? {
+                        let old: T = self
+                            .base_repo
+                            .deserialize(&bytes)
+                            .map_err(ConflictableTransactionError::Abort)?;
+                        for entry in index_entries(&old, key) {
+                            batch.remove(entry);
+                        }
+                        for entry in unique_entries(&old) {
+                            unique_tree.remove(entry)?;
+                        }
+                    }
+
+                    for entry in new_entries {
+                        batch.insert(entry.as_slice(), key.as_slice());
+                    }
+                    index_tree.apply_batch(&batch)?;
+
+                    // Checked here rather than before the transaction, so that
+                    // concurrent writes can't both take the same key
+                    for entry in new_unique {
+                        if unique_tree.get(entry)?.is_some() {
+                            return Err(ConflictableTransactionError::Abort(
+                                ModelError::InvalidData("Unique key already taken".to_string()),
+                            ));
+                        }
+                        unique_tree.insert(entry.as_slice(), key.as_slice())?;
+                    }
+
+                    match value {
+                        Some(value) => tree.insert(key.as_slice(), value.as_slice())?,
+                        None => tree.remove(key.as_slice())?,
+                    };
+                }
+
+                Ok(())
+            })
+            .map_err(|err| match err {
+                TransactionError::Abort(err) => err,
+                TransactionError::Storage(err) => err.into(),
+            })
+    }
+}
+
+/// Length-prefix both parts so one key can never be a prefix of another
+fn index_prefix(index_name: &str, key: &[u8]) -> Vec<u8> {
+    let mut prefix = Vec::with_capacity(8 + index_name.len() + key.len());
+    prefix.extend_from_slice(&(index_name.len() as u32).to_be_bytes());
+    prefix.extend_from_slice(index_name.as_bytes());
+    prefix.extend_from_slice(&(key.len() as u32).to_be_bytes());
+    prefix.extend_from_slice(key);
+    prefix
+}
+
+fn index_entries<T: IndexedModel>(model: &T, model_key: &[u8]) -> Vec<Vec<u8>> {
+    model
+        .index_keys()
+        .into_iter()
+        .map(|(index_name, key)| {
+            let mut entry = index_prefix(index_name, &key);
+            entry.extend_from_slice(model_key);
+            entry
+        })
+        .collect()
+}
+
+fn unique_entries<T: IndexedModel>(model: &T) -> Vec<Vec<u8>> {
+    model
+        .unique_keys()
+        .into_iter()
+        .map(|(index_name, key)| index_prefix(index_name, &key))
+        .collect()
+}
+
+impl<T: IndexedModel> Repository<T> for IndexedRepository<T> {
+    fn get(&self, id: &T::Id) -> Result<Option<T>, ModelError> {
+        self.base_repo.get(id)
+    }
+
+    fn insert(&self, model: &T) -> Result<(), ModelError> {
+        self.write(&[(model.id(), Some(model))])
+    }
+
+    fn update(&self, model: &T) -> Result<(), ModelError> {
+        self.write(&[(model.id(), Some(model))])
+    }
+
+    fn delete(&self, id: &T::Id) -> Result<(), ModelError> {
+        self.write(&[(id, None)])
+    }
+
+    fn list(&self) -> Result<Vec<T>, ModelError> {
+        self.base_repo.list()
+    }
+}
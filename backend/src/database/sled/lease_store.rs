@@ -0,0 +1,84 @@
+#![allow(dead_code)] // Will be used by the sync scheduler
+use crate::database::traits::ModelError;
+use serde::{Deserialize, Serialize};
+use sled::{Db, Tree};
+use time::{Duration, OffsetDateTime};
+
+const LEASES_TREE: &str = "scheduler_leases";
+
+/// Who currently owns the scheduling of a watcher, and until when
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Lease {
+    holder: String,
+    expires_at: OffsetDateTime,
+}
+
+/// Expiring per-watcher leases, so that only one scheduler syncs a watcher at a time.
+///
+/// A holder keeps its lease alive by acquiring it again before it expires; a
+/// lease that isn't renewed can be taken over by any other holder.
+#[derive(Debug, Clone)]
+pub struct SchedulerLeases {
+    tree: Tree,
+}
+
+impl SchedulerLeases {
+    /// Create a new lease store backed by the given database
+    pub fn new(db: Db) -> Result<Self, ModelError> {
+        Ok(Self {
+            tree: db.open_tree(LEASES_TREE)?,
+        })
+    }
+
+    /// Try to take or renew the lease on a watcher for `ttl`.
+    ///
+    /// Returns `false` if another holder owns an unexpired lease.
+    pub fn try_acquire(
+        &self,
+        watcher_id: u64,
+        holder: &str,
+        ttl: Duration,
+    ) -> Result<bool, ModelError> {
+        let key = watcher_id.to_be_bytes();
+
+        loop {
+            let now = OffsetDateTime::now_utc();
+            let current = self.tree.get(key)?;
+
+            if let Some(bytes) = &current {
+                let lease: Lease = bincode::deserialize(bytes)?;
+                if lease.holder != holder && lease.expires_at > now {
+                    return Ok(false);
+                }
+            }
+
+            let lease = Lease {
+                holder: holder.to_string(),
+                expires_at: now + ttl,
+            };
+            let swapped = self
+                .tree
+                .compare_and_swap(key, current, Some(bincode::serialize(&lease)?))?;
+
+            // Someone else changed the lease in the meantime, look at it again
+            if swapped.is_ok() {
+                return Ok(true);
+            }
+        }
+    }
+
+    /// Give up the lease on a watcher, if `holder` still owns it
+    pub fn release(&self, watcher_id: u64, holder: &str) -> Result<(), ModelError> {
+        let key = watcher_id.to_be_bytes();
+
+        if let Some(bytes) = self.tree.get(key)? {
+            let lease: Lease = bincode::deserialize(&bytes)?;
+            if lease.holder == holder {
+                // Losing this race means the lease was taken over, which is fine
+                let _ = self.tree.compare_and_swap(key, Some(bytes), None as Option<&[u8]>)?;
+            }
+        }
+
+        Ok(())
+    }
+}
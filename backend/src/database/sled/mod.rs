@@ -3,6 +3,18 @@ use serde::{de::DeserializeOwned, Serialize};
 use sled::Db;
 use std::marker::PhantomData;
 
+mod indexed_repository;
+mod lease_store;
+mod session_store;
+mod user_repository;
+mod watcher_repository;
+
+pub use indexed_repository::IndexedRepository;
+pub use lease_store::SchedulerLeases;
+pub use session_store::SledSessionStore;
+pub use user_repository::UserRepository;
+pub use watcher_repository::WatcherRepository;
+
 /// A base repository implementation using Sled
 pub struct SledRepository<T: DatabaseModel> {
     db: Db,
@@ -0,0 +1,109 @@
+use async_trait::async_trait;
+use sled::{Db, Tree};
+use time::OffsetDateTime;
+use tower_sessions::session::{Id, Record};
+use tower_sessions::session_store::{self, ExpiredDeletion, SessionStore};
+
+const SESSIONS_TREE: &str = "sessions";
+
+/// A `tower-sessions` store that keeps session records in a sled tree
+#[derive(Debug, Clone)]
+pub struct SledSessionStore {
+    tree: Tree,
+}
+
+impl SledSessionStore {
+    /// Create a new session store backed by the given database
+    pub fn new(db: Db) -> Result<Self, sled::Error> {
+        Ok(Self {
+            tree: db.open_tree(SESSIONS_TREE)?,
+        })
+    }
+
+    fn key(id: &Id) -> [u8; 16] {
+        id.0.to_be_bytes()
+    }
+
+    // Session data holds arbitrary JSON values, which bincode can't round-trip,
+    // so records are stored as JSON.
+    fn encode(record: &Record) -> session_store::Result<Vec<u8>> {
+        serde_json::to_vec(record).map_err(|e| session_store::Error::Encode(e.to_string()))
+    }
+
+    fn decode(bytes: &[u8]) -> session_store::Result<Record> {
+        serde_json::from_slice(bytes).map_err(|e| session_store::Error::Decode(e.to_string()))
+    }
+}
+
+fn backend_error(err: sled::Error) -> session_store::Error {
+    session_store::Error::Backend(err.to_string())
+}
+
+#[async_trait]
+impl SessionStore for SledSessionStore {
+    async fn create(&self, record: &mut Record) -> session_store::Result<()> {
+        // Regenerate the ID until it doesn't collide with an existing session
+        loop {
+            let value = Self::encode(record)?;
+            let inserted = self
+                .tree
+                .compare_and_swap(Self::key(&record.id), None as Option<&[u8]>, Some(value))
+                .map_err(backend_error)?;
+
+            if inserted.is_ok() {
+                return Ok(());
+            }
+
+            record.id = Id::default();
+        }
+    }
+
+    async fn save(&self, record: &Record) -> session_store::Result<()> {
+        self.tree
+            .insert(Self::key(&record.id), Self::encode(record)?)
+            .map_err(backend_error)?;
+        Ok(())
+    }
+
+    async fn load(&self, session_id: &Id) -> session_store::Result<Option<Record>> {
+        let Some(bytes) = self.tree.get(Self::key(session_id)).map_err(backend_error)? else {
+            return Ok(None);
+        };
+
+        let record = Self::decode(&bytes)?;
+        if record.expiry_date <= OffsetDateTime::now_utc() {
+            return Ok(None);
+        }
+
+        Ok(Some(record))
+    }
+
+    async fn delete(&self, session_id: &Id) -> session_store::Result<()> {
+        self.tree
+            .remove(Self::key(session_id))
+            .map_err(backend_error)?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ExpiredDeletion for SledSessionStore {
+    async fn delete_expired(&self) -> session_store::Result<()> {
+        let now = OffsetDateTime::now_utc();
+
+        for entry in self.tree.iter() {
+            let (key, value) = entry.map_err(backend_error)?;
+            let expired = match Self::decode(&value) {
+                Ok(record) => record.expiry_date <= now,
+                // Records we can no longer read are as good as expired
+                Err(_) => true,
+            };
+
+            if expired {
+                self.tree.remove(key).map_err(backend_error)?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -1,58 +1,51 @@
-use super::SledRepository;
+use super::IndexedRepository;
 use crate::database::{
-    models::User,
+    models::{User, USERNAME_INDEX},
     traits::{ModelError, Repository},
 };
-use serde::{de::DeserializeOwned, Serialize};
 use sled::Db;
 
 const USERS_TREE: &str = "users";
-const USERNAME_INDEX_TREE: &str = "users_username_index";
 
 /// A repository for managing users with additional user-specific functionality
 pub struct UserRepository {
-    base_repo: SledRepository<User>,
-    username_index: SledRepository<Vec<u8>>,
+    db: Db,
+    base_repo: IndexedRepository<User>,
 }
 
 impl UserRepository {
     /// Create a new user repository
     pub fn new(db: Db) -> Result<Self, ModelError> {
         Ok(Self {
-            base_repo: SledRepository::new(db.clone(), USERS_TREE)?,
-            username_index: SledRepository::new(db, USERNAME_INDEX_TREE)?,
+            base_repo: IndexedRepository::new(db.clone(), USERS_TREE)?,
+            db,
         })
     }
 
-    /// Get a user by their username
+    /// Get a user by their username, ignoring case
     pub fn get_by_username(&self, username: &str) -> Result<Option<User>, ModelError> {
-        if let Some(id_bytes) = self.username_index.get(&username.as_bytes().to_vec())? {
-            let id = bincode::deserialize(&id_bytes)?;
-            self.base_repo.get(&id)
-        } else {
-            Ok(None)
-        }
+        Ok(self
+            .base_repo
+            .find_by_index(USERNAME_INDEX, &User::username_key(username))?
+            .into_iter()
+            .next())
     }
 
-    /// Create a new user with the given credentials
+    /// Create a new user with the given credentials, hashing the password.
+    ///
+    /// Fails with `ModelError::InvalidData` if the username is taken in any casing.
     pub fn create_user(&self, username: String, password: String) -> Result<User, ModelError> {
-        // Check if username already exists
-        if self.get_by_username(&username)?.is_some() {
-            return Err(ModelError::InvalidData("Username already exists".to_string()));
-        }
+        let id = i64::try_from(self.db.generate_id()?)
+            .map_err(|_| ModelError::InvalidData("Out of user IDs".to_string()))?;
+        let user = User::with_credentials(id, username, password_auth::generate_hash(password));
+
+        self.base_repo.insert(&user).map_err(|err| match err {
+            ModelError::InvalidData(_) => {
+                ModelError::InvalidData("Username already exists".to_string())
+            }
+            err => err,
+        })?;
 
-        // Generate a new ID (this is a simple implementation - in production, you'd want a better ID generation strategy)
-        let id = self.base_repo.list()?.len() as i64 + 1;
-        
-        // Create the user
-        let user = User::with_credentials(id, username.clone(), password);
-        
-        // Store the user
-        self.base_repo.insert(&user)?;
-        
-        // Create username index
-        self.username_index.insert(&username.as_bytes().to_vec(), &id.to_be_bytes().to_vec())?;
-        
         Ok(user)
     }
 }
@@ -63,35 +56,18 @@ impl Repository<User> for UserRepository {
     }
 
     fn insert(&self, model: &User) -> Result<(), ModelError> {
-        // Update username index
-        self.username_index.insert(
-            &model.username().as_bytes().to_vec(),
-            &model.id().to_be_bytes().to_vec(),
-        )?;
-        
         self.base_repo.insert(model)
     }
 
     fn update(&self, model: &User) -> Result<(), ModelError> {
-        // Update username index
-        self.username_index.insert(
-            &model.username().as_bytes().to_vec(),
-            &model.id().to_be_bytes().to_vec(),
-        )?;
-        
         self.base_repo.update(model)
     }
 
     fn delete(&self, id: &i64) -> Result<(), ModelError> {
-        if let Some(user) = self.base_repo.get(id)? {
-            // Remove username index
-            self.username_index.delete(&user.username().as_bytes().to_vec())?;
-        }
-        
         self.base_repo.delete(id)
     }
 
     fn list(&self) -> Result<Vec<User>, ModelError> {
         self.base_repo.list()
     }
-} 
\ No newline at end of file
+}
@@ -0,0 +1,134 @@
+use super::IndexedRepository;
+use crate::database::{
+    models::{Watcher, OWNER_INDEX, OWNER_NAME_INDEX},
+    traits::{ModelError, Repository},
+};
+use sled::Db;
+use time::OffsetDateTime;
+
+const WATCHERS_TREE: &str = "watchers";
+
+/// A repository for managing the watchers of all users
+pub struct WatcherRepository {
+    db: Db,
+    base_repo: IndexedRepository<Watcher>,
+}
+
+impl WatcherRepository {
+    /// Create a new watcher repository
+    pub fn new(db: Db) -> Result<Self, ModelError> {
+        Ok(Self {
+            base_repo: IndexedRepository::new(db.clone(), WATCHERS_TREE)?,
+            db,
+        })
+    }
+
+    /// List the watchers owned by a user, skipping archived ones unless asked for
+    pub fn list_for_owner(
+        &self,
+        owner_id: i64,
+        include_archived: bool,
+    ) -> Result<Vec<Watcher>, ModelError> {
+        let mut watchers = self
+            .base_repo
+            .find_by_index(OWNER_INDEX, &owner_id.to_be_bytes())?;
+        watchers.retain(|watcher| include_archived || watcher.archived_at.is_none());
+        watchers.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(watchers)
+    }
+
+    /// List the watchers owned by a user whose name contains `query`, ignoring case
+    pub fn search_for_owner(
+        &self,
+        owner_id: i64,
+        query: &str,
+        include_archived: bool,
+    ) -> Result<Vec<Watcher>, ModelError> {
+        let query = query.to_lowercase();
+        let mut watchers = self.list_for_owner(owner_id, include_archived)?;
+        watchers.retain(|watcher| watcher.name.to_lowercase().contains(&query));
+        Ok(watchers)
+    }
+
+    /// Get a user's watcher by its name
+    pub fn get_by_name(&self, owner_id: i64, name: &str) -> Result<Option<Watcher>, ModelError> {
+        Ok(self
+            .base_repo
+            .find_by_index(OWNER_NAME_INDEX, &Watcher::owner_name_key(owner_id, name))?
+            .into_iter()
+            .next())
+    }
+
+    /// Archive a watcher: it stops syncing but is kept, along with its name
+    pub fn archive_watcher(&self, id: &u64) -> Result<Watcher, ModelError> {
+        let mut watcher = self.base_repo.get(id)?.ok_or(ModelError::NotFound)?;
+
+        if watcher.archived_at.is_none() {
+            watcher.active = false;
+            watcher.archived_at = Some(OffsetDateTime::now_utc());
+            self.base_repo.update(&watcher)?;
+        }
+
+        Ok(watcher)
+    }
+
+    /// Create a new watcher, letting `configure` set it up before it's stored.
+    ///
+    /// Fails with `ModelError::InvalidData` if the owner already uses the name.
+    pub fn create_watcher(
+        &self,
+        owner_id: i64,
+        name: String,
+        mut configure: impl FnMut(&mut Watcher),
+    ) -> Result<Watcher, ModelError> {
+        let mut watchers =
+            self.create_watchers(owner_id, vec![name], |_, watcher| configure(watcher))?;
+
+        Ok(watchers.remove(0))
+    }
+
+    /// Create several watchers at once, letting `configure` set up each one
+    /// before it's stored.
+    ///
+    /// Either all watchers are created or, if any name is taken or repeated, none.
+    pub fn create_watchers(
+        &self,
+        owner_id: i64,
+        names: Vec<String>,
+        mut configure: impl FnMut(usize, &mut Watcher),
+    ) -> Result<Vec<Watcher>, ModelError> {
+        let mut watchers = Vec::with_capacity(names.len());
+
+        for (index, name) in names.into_iter().enumerate() {
+            let mut watcher = Watcher::with_owner(self.db.generate_id()?, owner_id, name);
+            configure(index, &mut watcher);
+            watchers.push(watcher);
+        }
+
+        self.base_repo.insert_all(&watchers)?;
+
+        Ok(watchers)
+    }
+}
+
+impl Repository<Watcher> for WatcherRepository {
+    fn get(&self, id: &u64) -> Result<Option<Watcher>, ModelError> {
+        self.base_repo.get(id)
+    }
+
+    fn insert(&self, model: &Watcher) -> Result<(), ModelError> {
+        self.base_repo.insert(model)
+    }
+
+    fn update(&self, model: &Watcher) -> Result<(), ModelError> {
+        self.base_repo.update(model)
+    }
+
+    fn delete(&self, id: &u64) -> Result<(), ModelError> {
+        self.base_repo.delete(id)
+    }
+
+    fn list(&self) -> Result<Vec<Watcher>, ModelError> {
+        self.base_repo.list()
+    }
+}
@@ -0,0 +1,34 @@
+use super::*;
+use crate::database::sled::SchedulerLeases;
+use time::Duration;
+
+#[test]
+fn test_only_one_instance_acquires_a_lease() {
+    let (_temp_dir, db) = setup_test_db();
+    let first = SchedulerLeases::new(db.clone()).unwrap();
+    let second = SchedulerLeases::new(db).unwrap();
+
+    assert!(first.try_acquire(1, "instance-a", Duration::minutes(1)).unwrap());
+    assert!(!second.try_acquire(1, "instance-b", Duration::minutes(1)).unwrap());
+
+    // Leases are per watcher, and the holder can renew its own
+    assert!(second.try_acquire(2, "instance-b", Duration::minutes(1)).unwrap());
+    assert!(first.try_acquire(1, "instance-a", Duration::minutes(1)).unwrap());
+
+    // Releasing someone else's lease does nothing
+    second.release(1, "instance-b").unwrap();
+    assert!(!second.try_acquire(1, "instance-b", Duration::minutes(1)).unwrap());
+
+    first.release(1, "instance-a").unwrap();
+    assert!(second.try_acquire(1, "instance-b", Duration::minutes(1)).unwrap());
+}
+
+#[test]
+fn test_expired_lease_can_be_taken_over() {
+    let (_temp_dir, db) = setup_test_db();
+    let leases = SchedulerLeases::new(db).unwrap();
+
+    assert!(leases.try_acquire(1, "instance-a", Duration::ZERO).unwrap());
+    assert!(leases.try_acquire(1, "instance-b", Duration::minutes(1)).unwrap());
+    assert!(!leases.try_acquire(1, "instance-a", Duration::minutes(1)).unwrap());
+}
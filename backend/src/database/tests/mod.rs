@@ -1,15 +1,13 @@
+mod lease_tests;
 mod sled_repository_tests;
 mod user_tests;
+mod watcher_tests;
 
-use crate::database::traits::ModelError;
 use crate::database::models::User;
-use crate::database::sled::{SledRepository, UserRepository};
+use crate::database::sled::UserRepository;
+use crate::database::traits::{CreatableModel, DatabaseModel, Repository, UpdatableModel};
 use tempfile::tempdir;
 
-// Re-export test modules
-pub use sled_repository_tests::*;
-pub use user_tests::*;
-
 // Common test utilities
 pub fn setup_test_db() -> (tempfile::TempDir, sled::Db) {
     let temp_dir = tempdir().unwrap();
@@ -21,4 +19,4 @@ pub fn setup_test_db() -> (tempfile::TempDir, sled::Db) {
 pub fn create_test_user(repo: &UserRepository, username: &str, password: &str) -> User {
     repo.create_user(username.to_string(), password.to_string())
         .unwrap()
-} 
\ No newline at end of file
+}
@@ -1,10 +1,6 @@
 use super::*;
-use crate::database::{
-    models::User,
-    sled::{SledRepository, UserRepository},
-    traits::{DatabaseModel, Repository},
-};
-use tempfile::tempdir;
+use crate::database::sled::{IndexedRepository, SledRepository};
+use crate::database::traits::IndexedModel;
 
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 struct TestModel {
@@ -20,10 +16,24 @@ impl DatabaseModel for TestModel {
     }
 }
 
-fn setup_test_db() -> (tempfile::TempDir, sled::Db) {
-    let temp_dir = tempdir().unwrap();
-    let db = sled::open(temp_dir.path()).unwrap();
-    (temp_dir, db)
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+struct IndexedTestModel {
+    id: i64,
+    group: String,
+}
+
+impl DatabaseModel for IndexedTestModel {
+    type Id = i64;
+
+    fn id(&self) -> &Self::Id {
+        &self.id
+    }
+}
+
+impl IndexedModel for IndexedTestModel {
+    fn index_keys(&self) -> Vec<(&'static str, Vec<u8>)> {
+        vec![("group", self.group.as_bytes().to_vec())]
+    }
 }
 
 #[test]
@@ -82,12 +92,12 @@ fn test_user_repository_operations() {
     let user = repo
         .create_user("test_user".to_string(), "password123".to_string())
         .unwrap();
-    assert_eq!(user.id(), &1);
+    let id = *user.id();
     assert_eq!(user.username(), "test_user");
 
     // Test get by username
     let retrieved = repo.get_by_username("test_user").unwrap().unwrap();
-    assert_eq!(retrieved.id(), &1);
+    assert_eq!(retrieved.id(), &id);
     assert_eq!(retrieved.username(), "test_user");
 
     // Test duplicate username
@@ -99,35 +109,117 @@ fn test_user_repository_operations() {
     ));
 
     // Test get by id
-    let retrieved = repo.get(&1).unwrap().unwrap();
-    assert_eq!(retrieved.id(), &1);
+    let retrieved = repo.get(&id).unwrap().unwrap();
+    assert_eq!(retrieved.id(), &id);
     assert_eq!(retrieved.username(), "test_user");
 
     // Test update
     let mut updated = retrieved.clone();
     updated.update(&User::with_credentials(
-        1,
+        id,
         "new_username".to_string(),
         "new_password".to_string(),
     ));
     repo.update(&updated).unwrap();
-    let retrieved = repo.get(&1).unwrap().unwrap();
+    let retrieved = repo.get(&id).unwrap().unwrap();
     assert_eq!(retrieved.username(), "new_username");
 
     // Test delete
-    repo.delete(&1).unwrap();
-    assert!(repo.get(&1).unwrap().is_none());
+    repo.delete(&id).unwrap();
+    assert!(repo.get(&id).unwrap().is_none());
     assert!(repo.get_by_username("new_username").unwrap().is_none());
 
     // Test list
-    let user1 = repo
-        .create_user("user1".to_string(), "pass1".to_string())
-        .unwrap();
-    let user2 = repo
-        .create_user("user2".to_string(), "pass2".to_string())
-        .unwrap();
+    let user1 = create_test_user(&repo, "user1", "pass1");
+    let user2 = create_test_user(&repo, "user2", "pass2");
     let users = repo.list().unwrap();
     assert_eq!(users.len(), 2);
-    assert!(users.iter().any(|u| u.id() == &1 && u.username() == "user1"));
-    assert!(users.iter().any(|u| u.id() == &2 && u.username() == "user2"));
-} 
\ No newline at end of file
+    assert!(users.iter().any(|u| u.id() == user1.id() && u.username() == "user1"));
+    assert!(users.iter().any(|u| u.id() == user2.id() && u.username() == "user2"));
+}
+
+#[test]
+fn test_usernames_are_case_insensitive() {
+    let (_temp_dir, db) = setup_test_db();
+    let repo = UserRepository::new(db).unwrap();
+
+    let bob = create_test_user(&repo, "Bob", "password123");
+
+    // Test duplicate username in another casing
+    let result = repo.create_user("bob".to_string(), "password456".to_string());
+    assert!(matches!(
+        result,
+        Err(crate::database::traits::ModelError::InvalidData(_))
+    ));
+
+    // Test lookup in any casing keeps the original spelling
+    let retrieved = repo.get_by_username("BOB").unwrap().unwrap();
+    assert_eq!(retrieved.id(), bob.id());
+    assert_eq!(retrieved.username(), "Bob");
+
+    // Test renaming to another casing of a taken username leaves both users alone
+    let alice = create_test_user(&repo, "alice", "password123");
+    let mut renamed = bob.clone();
+    renamed.username = "Alice".to_string();
+    assert!(matches!(
+        repo.update(&renamed),
+        Err(crate::database::traits::ModelError::InvalidData(_))
+    ));
+    assert_eq!(repo.get(bob.id()).unwrap().unwrap().username(), "Bob");
+    assert_eq!(repo.get_by_username("ALICE").unwrap().unwrap().id(), alice.id());
+    assert_eq!(repo.get_by_username("bob").unwrap().unwrap().id(), bob.id());
+}
+
+#[test]
+fn test_user_repository_hashes_passwords() {
+    let (_temp_dir, db) = setup_test_db();
+    let repo = UserRepository::new(db).unwrap();
+
+    let user = create_test_user(&repo, "test_user", "password123");
+    assert_ne!(user.password_hash(), "password123");
+    assert!(user.password_hash().starts_with("$argon2"));
+
+    let retrieved = repo.get_by_username("test_user").unwrap().unwrap();
+    assert!(retrieved.verify_password("password123").unwrap());
+    assert!(!retrieved.verify_password("wrongpassword").unwrap());
+}
+
+#[test]
+fn test_indexed_repository_operations() {
+    let (_temp_dir, db) = setup_test_db();
+    let repo = IndexedRepository::<IndexedTestModel>::new(db.clone(), "indexed_models").unwrap();
+
+    let model = |id: i64, group: &str| IndexedTestModel {
+        id,
+        group: group.to_string(),
+    };
+    let (first, second, third) = (model(1, "a"), model(2, "a"), model(3, "ab"));
+    repo.insert(&first).unwrap();
+    repo.insert(&second).unwrap();
+    repo.insert(&third).unwrap();
+
+    // Test find by index, which must not match longer keys sharing a prefix
+    let mut ids: Vec<_> = repo
+        .find_by_index("group", b"a")
+        .unwrap()
+        .iter()
+        .map(|m| m.id)
+        .collect();
+    ids.sort();
+    assert_eq!(ids, vec![first.id, second.id]);
+    assert!(repo.find_by_index("group", b"b").unwrap().is_empty());
+    assert!(repo.find_by_index("other", b"a").unwrap().is_empty());
+
+    // Test update moves the model between index keys
+    repo.update(&model(second.id, "b")).unwrap();
+    assert_eq!(repo.find_by_index("group", b"a").unwrap().len(), 1);
+    assert_eq!(repo.find_by_index("group", b"b").unwrap()[0].id, second.id);
+
+    // Test delete removes the index entries
+    repo.delete(&first.id).unwrap();
+    repo.delete(&second.id).unwrap();
+    repo.delete(&third.id).unwrap();
+    assert!(repo.find_by_index("group", b"a").unwrap().is_empty());
+    assert!(repo.list().unwrap().is_empty());
+    assert!(db.open_tree("indexed_models_indexes").unwrap().is_empty());
+}
@@ -1,5 +1,11 @@
 use super::*;
-use crate::database::models::User;
+use crate::database::Database;
+use crate::users::{Backend, Credentials};
+use axum_login::AuthnBackend;
+
+/// Hash of "password123" made with much weaker Argon2 parameters than the defaults
+const OBSOLETE_HASH: &str =
+    "$argon2id$v=19$m=8,t=1,p=1$c29tZXNhbHRzb21lc2FsdA$WoL8f/NuW7CMCgVu/q7Unpd0uNpaIeKRSrnyYrvY0LQ";
 
 #[test]
 fn test_user_creation() {
@@ -33,9 +39,12 @@ fn test_user_update() {
 
 #[test]
 fn test_user_password_verification() {
-    let user = User::with_credentials(1, "test_user".to_string(), "password123".to_string());
-    
-    // This is a simple test - in reality, we'd want to test with proper password hashing
+    let user = User::with_credentials(
+        1,
+        "test_user".to_string(),
+        password_auth::generate_hash("password123"),
+    );
+
     assert!(user.verify_password("password123").unwrap());
     assert!(!user.verify_password("wrongpassword").unwrap());
 }
@@ -49,4 +58,54 @@ fn test_user_debug_implementation() {
     assert!(debug_output.contains("username: \"test_user\""));
     assert!(debug_output.contains("password: \"[redacted]\""));
     assert!(!debug_output.contains("password123"));
-} 
\ No newline at end of file
+} 
+
+#[tokio::test]
+async fn test_authenticate_rehashes_obsolete_password() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let db = Database::open(temp_dir.path()).unwrap();
+    let users = db.users().unwrap();
+    let old_user = User::with_credentials(1, "old_user".to_string(), OBSOLETE_HASH.to_string());
+    users.insert(&old_user).unwrap();
+    assert!(old_user.password_hash_is_obsolete());
+
+    let backend = Backend::new(db);
+    let credentials = |password: &str| Credentials {
+        username: "old_user".to_string(),
+        password: password.to_string(),
+        next: None,
+    };
+
+    // A wrong password leaves the stored hash alone
+    assert!(backend.authenticate(credentials("wrong")).await.unwrap().is_none());
+    assert_eq!(users.get(&1).unwrap().unwrap().password_hash(), OBSOLETE_HASH);
+
+    let user = backend
+        .authenticate(credentials("password123"))
+        .await
+        .unwrap()
+        .unwrap();
+    let stored = users.get(&1).unwrap().unwrap();
+    assert_eq!(stored.password_hash(), user.password_hash());
+    assert!(!stored.password_hash_is_obsolete());
+    assert!(stored.verify_password("password123").unwrap());
+}
+
+#[tokio::test]
+async fn test_initialize_promotes_admins() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let db = Database::open(temp_dir.path()).unwrap();
+    let users = db.users().unwrap();
+    let alice = create_test_user(&users, "alice", "password123");
+    let bob = create_test_user(&users, "bob", "password123");
+    assert!(!alice.is_admin);
+
+    Backend::new(db)
+        .initialize(&["Alice", "missing"])
+        .await
+        .unwrap();
+
+    assert!(users.get(alice.id()).unwrap().unwrap().is_admin);
+    assert!(!users.get(bob.id()).unwrap().unwrap().is_admin);
+    assert!(!users.get_by_username("ferris").unwrap().unwrap().is_admin);
+}
@@ -0,0 +1,147 @@
+use super::*;
+use crate::database::{
+    models::Watcher,
+    sled::WatcherRepository,
+    traits::{DatabaseModel, ModelError, Repository},
+};
+use time::{macros::datetime, Duration};
+
+#[test]
+fn test_next_sync_at_for_synced_watcher() {
+    let mut watcher = Watcher::with_owner(1, 1, "road trip".to_string());
+    watcher.active = true;
+    watcher.sync_frequency_secs = 15 * 60;
+    watcher.last_synced_at = Some(datetime!(2025-03-01 12:00 UTC));
+
+    assert_eq!(watcher.next_sync_at(), Some(datetime!(2025-03-01 12:15 UTC)));
+}
+
+#[test]
+fn test_next_sync_at_for_unsynced_watcher() {
+    let mut watcher = Watcher::with_owner(1, 1, "road trip".to_string());
+    watcher.active = true;
+
+    assert_eq!(watcher.next_sync_at(), None);
+}
+
+#[test]
+fn test_next_sync_at_for_inactive_watcher() {
+    let mut watcher = Watcher::with_owner(1, 1, "road trip".to_string());
+    watcher.last_synced_at = Some(datetime!(2025-03-01 12:00 UTC));
+
+    assert_eq!(watcher.next_sync_at(), None);
+}
+
+#[test]
+fn test_next_sync_at_for_cron_watcher() {
+    let mut watcher = Watcher::with_owner(1, 1, "road trip".to_string());
+    watcher.active = true;
+    watcher.cron_expression = Some("0 3 * * *".to_string());
+    watcher.last_synced_at = Some(datetime!(2025-03-01 12:00 UTC));
+
+    assert_eq!(watcher.next_sync_at(), Some(datetime!(2025-03-02 03:00 UTC)));
+}
+
+#[test]
+fn test_parse_cron_expression() {
+    assert!(Watcher::parse_cron_expression("0 3 * * *").is_ok());
+    assert!(Watcher::parse_cron_expression("30 0 3 * * MON").is_ok());
+    assert!(Watcher::parse_cron_expression("every day at 3").is_err());
+    assert!(Watcher::parse_cron_expression("61 * * * *").is_err());
+}
+
+#[test]
+fn test_normalize_market() {
+    assert_eq!(Watcher::normalize_market("de"), Some("DE".to_string()));
+    assert_eq!(Watcher::normalize_market(" Us "), Some("US".to_string()));
+    assert_eq!(Watcher::normalize_market("DEU"), None);
+    assert_eq!(Watcher::normalize_market("1A"), None);
+    assert_eq!(Watcher::normalize_market(""), None);
+}
+
+#[test]
+fn test_watcher_repository_operations() {
+    let (_temp_dir, db) = setup_test_db();
+    let repo = WatcherRepository::new(db).unwrap();
+
+    // Test create watcher
+    let watcher = repo.create_watcher(1, "road trip".to_string(), |_| {}).unwrap();
+    assert_eq!(watcher.owner_id, 1);
+    assert!(!watcher.active);
+    assert_eq!(watcher.market, None);
+
+    // Test duplicate name for the same owner
+    let result = repo.create_watcher(1, "road trip".to_string(), |_| {});
+    assert!(matches!(result, Err(ModelError::InvalidData(_))));
+
+    // The same name is fine for another owner
+    repo.create_watcher(2, "road trip".to_string(), |_| {}).unwrap();
+
+    // Names are unique even when written without checking first
+    let result = repo.insert(&Watcher::with_owner(u64::MAX - 1, 1, "road trip".to_string()));
+    assert!(matches!(result, Err(ModelError::InvalidData(_))));
+    assert!(repo.get(&(u64::MAX - 1)).unwrap().is_none());
+
+    // Test get by name
+    let retrieved = repo.get_by_name(1, "road trip").unwrap().unwrap();
+    assert_eq!(retrieved.id(), watcher.id());
+    assert!(repo.get_by_name(1, "missing").unwrap().is_none());
+
+    // Test update
+    let mut updated = retrieved.clone();
+    updated.last_synced_at = Some(datetime!(2025-03-01 12:00 UTC));
+    repo.update(&updated).unwrap();
+    let retrieved = repo.get(watcher.id()).unwrap().unwrap();
+    assert_eq!(
+        retrieved.last_synced_at.unwrap() + Duration::hours(1),
+        datetime!(2025-03-01 13:00 UTC)
+    );
+
+    // Test list for owner
+    repo.create_watcher(1, "gym".to_string(), |_| {}).unwrap();
+    let names: Vec<_> = repo
+        .list_for_owner(1, false)
+        .unwrap()
+        .into_iter()
+        .map(|watcher| watcher.name)
+        .collect();
+    assert_eq!(names, vec!["gym", "road trip"]);
+
+    // Test search for owner
+    repo.create_watcher(1, "Road Rage".to_string(), |_| {}).unwrap();
+    let names: Vec<_> = repo
+        .search_for_owner(1, "ROAD", false)
+        .unwrap()
+        .into_iter()
+        .map(|watcher| watcher.name)
+        .collect();
+    assert_eq!(names, vec!["Road Rage", "road trip"]);
+    assert!(repo.search_for_owner(2, "gym", false).unwrap().is_empty());
+
+    // Test batch creation is all or nothing
+    let result = repo.create_watchers(3, vec!["a".to_string(), "a".to_string()], |_, _| {});
+    assert!(matches!(result, Err(ModelError::InvalidData(_))));
+    assert!(repo.list_for_owner(3, true).unwrap().is_empty());
+    let created = repo
+        .create_watchers(3, vec!["a".to_string(), "b".to_string()], |index, watcher| {
+            watcher.sync_frequency_secs = 60 * (index as u64 + 1);
+        })
+        .unwrap();
+    assert_eq!(created[1].sync_frequency_secs, 120);
+    assert_eq!(repo.get_by_name(3, "b").unwrap().unwrap().sync_frequency_secs, 120);
+    let result = repo.create_watchers(3, vec!["c".to_string(), "a".to_string()], |_, _| {});
+    assert!(matches!(result, Err(ModelError::InvalidData(_))));
+    assert!(repo.get_by_name(3, "c").unwrap().is_none());
+
+    // Test archive keeps the watcher but hides it from the default list
+    let mut gym = repo.get_by_name(1, "gym").unwrap().unwrap();
+    gym.active = true;
+    repo.update(&gym).unwrap();
+    let archived = repo.archive_watcher(gym.id()).unwrap();
+    assert!(!archived.active);
+    assert!(archived.archived_at.is_some());
+    assert_eq!(repo.list_for_owner(1, false).unwrap().len(), 2);
+    assert_eq!(repo.list_for_owner(1, true).unwrap().len(), 3);
+    assert!(repo.get_by_name(1, "gym").unwrap().is_some());
+    assert!(matches!(repo.archive_watcher(&u64::MAX), Err(ModelError::NotFound)));
+}
@@ -1,4 +1,4 @@
-use serde::{de::DeserializeOwned, Serialize};
+use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 
 /// A trait for database models that can be stored and retrieved
@@ -11,17 +11,34 @@ pub trait DatabaseModel: Serialize + for<'de> Deserialize<'de> + Debug + Clone {
 }
 
 /// A trait for models that can be created
+#[allow(dead_code)] // Only exercised by tests so far
 pub trait CreatableModel: DatabaseModel {
     /// Create a new instance of the model
     fn new(id: Self::Id) -> Self;
 }
 
 /// A trait for models that can be updated
+#[allow(dead_code)] // Only exercised by tests so far
 pub trait UpdatableModel: DatabaseModel {
     /// Update the model with new data
     fn update(&mut self, other: &Self);
 }
 
+/// A trait for models that can be looked up by fields other than their ID
+pub trait IndexedModel: DatabaseModel {
+    /// The `(index_name, key)` pairs this model can be found under.
+    ///
+    /// An index may hold the same key for several models.
+    fn index_keys(&self) -> Vec<(&'static str, Vec<u8>)>;
+
+    /// The `(index_name, key)` pairs no two models may share.
+    ///
+    /// Writing a model that shares one with another fails with `ModelError::InvalidData`.
+    fn unique_keys(&self) -> Vec<(&'static str, Vec<u8>)> {
+        Vec::new()
+    }
+}
+
 /// A trait for repositories that can store and retrieve models
 pub trait Repository<T: DatabaseModel> {
     /// Get a model by its ID
@@ -34,6 +51,7 @@ pub trait Repository<T: DatabaseModel> {
     fn update(&self, model: &T) -> Result<(), ModelError>;
     
     /// Delete a model by its ID
+    #[allow(dead_code)] // Will be used by the endpoints deleting users and watchers
     fn delete(&self, id: &T::Id) -> Result<(), ModelError>;
     
     /// List all models
@@ -55,7 +73,3 @@ pub enum ModelError {
     #[error("Invalid data: {0}")]
     InvalidData(String),
 }
-
-// Re-export commonly used types
-pub use serde::{Deserialize, Serialize};
-pub use sled; 
\ No newline at end of file
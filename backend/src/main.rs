@@ -16,6 +16,7 @@ mod api;
 mod app;
 mod config;
 mod database;
+mod metrics;
 mod users;
 
 #[tokio::main]
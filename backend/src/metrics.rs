@@ -0,0 +1,57 @@
+use std::time::Duration;
+
+use ::metrics::{counter, histogram};
+use metrics_exporter_prometheus::{BuildError, PrometheusBuilder, PrometheusHandle};
+
+const SONGLINK_REQUESTS: &str = "songlink_requests_total";
+const SONGLINK_REQUEST_DURATION: &str = "songlink_request_duration_seconds";
+
+// How often histograms are compacted between scrapes
+const UPKEEP_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Install the global Prometheus recorder.
+///
+/// Metrics recorded before this are dropped. The returned handle renders the
+/// current values for the `/metrics` endpoint.
+pub fn install() -> Result<PrometheusHandle, BuildError> {
+    let handle = PrometheusBuilder::new().install_recorder()?;
+
+    let upkeep_handle = handle.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(UPKEEP_INTERVAL);
+        loop {
+            interval.tick().await;
+            upkeep_handle.run_upkeep();
+        }
+    });
+
+    Ok(handle)
+}
+
+/// Record one call to the Songlink API, `outcome` being "ok", "error" or "timeout"
+pub fn record_songlink_request(outcome: &'static str, duration: Duration) {
+    counter!(SONGLINK_REQUESTS, "outcome" => outcome).increment(1);
+    histogram!(SONGLINK_REQUEST_DURATION).record(duration.as_secs_f64());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_songlink_requests_are_rendered() {
+        let recorder = PrometheusBuilder::new().build_recorder();
+        let handle = recorder.handle();
+
+        ::metrics::with_local_recorder(&recorder, || {
+            record_songlink_request("ok", Duration::from_millis(120));
+            record_songlink_request("timeout", Duration::from_secs(10));
+            record_songlink_request("ok", Duration::from_millis(80));
+        });
+
+        let rendered = handle.render();
+        assert!(rendered.contains(r#"songlink_requests_total{outcome="ok"} 2"#));
+        assert!(rendered.contains(r#"songlink_requests_total{outcome="timeout"} 1"#));
+        assert!(rendered.contains("songlink_request_duration_seconds_count 3"));
+    }
+}
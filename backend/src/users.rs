@@ -1,8 +1,13 @@
+use axum::{
+    extract::FromRequestParts,
+    http::{request::Parts, StatusCode},
+    response::{IntoResponse, Response},
+};
 use axum_login::{AuthUser, AuthnBackend, UserId};
 use serde::Deserialize;
 use tokio::task;
 
-use crate::database::{Database, DatabaseError, User, UserRepository};
+use crate::database::{Database, DatabaseError, DatabaseModel, ModelError, Repository, User};
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct Credentials {
@@ -16,6 +21,9 @@ pub enum Error {
     #[error(transparent)]
     Database(#[from] DatabaseError),
 
+    #[error(transparent)]
+    Model(#[from] ModelError),
+
     #[error(transparent)]
     TaskJoin(#[from] task::JoinError),
 }
@@ -30,23 +38,47 @@ impl Backend {
         Self { db }
     }
     
-    /// Insert the test user during initialization
-    pub async fn initialize(&self) -> Result<(), Error> {
+    /// Insert the test user during initialization, and promote the given users to admins.
+    ///
+    /// Promotion is permanent; removing a username from the list later doesn't demote it.
+    pub async fn initialize(&self, admin_usernames: &[&str]) -> Result<(), Error> {
         let users = self.db.users()?;
         
         // Check if test user exists
         if users.get_by_username("ferris")?.is_none() {
             // Create test user (the original ferris account)
-            users.create_user(
-                "ferris".to_string(),
-                "$argon2id$v=19$m=19456,t=2,p=1$VE0e3g7DalWHgDwou3nuRA$uC6TER156UQpk0lNQ5+jHM0l5poVjPA1he/Tyn9J4Zw".to_string(),
-            )?;
+            users.create_user("ferris".to_string(), "hunter42".to_string())?;
+        }
+
+        for username in admin_usernames {
+            match users.get_by_username(username)? {
+                Some(mut user) if !user.is_admin => {
+                    user.is_admin = true;
+                    users.update(&user)?;
+                    tracing::info!("Promoted {} to admin", user.username());
+                }
+                Some(_) => {}
+                None => tracing::warn!("Admin user {} doesn't exist", username),
+            }
         }
         
         Ok(())
     }
 }
 
+impl AuthUser for User {
+    type Id = i64;
+
+    fn id(&self) -> Self::Id {
+        *DatabaseModel::id(self)
+    }
+
+    fn session_auth_hash(&self) -> &[u8] {
+        // Changing the password invalidates existing sessions
+        self.password_hash().as_bytes()
+    }
+}
+
 #[async_trait::async_trait]
 impl AuthnBackend for Backend {
     type User = User;
@@ -68,11 +100,19 @@ impl AuthnBackend for Backend {
         // Verifying the password is blocking and potentially slow, so we'll do so via
         // `spawn_blocking`.
         task::spawn_blocking(move || {
-            Ok(if user.verify_password(&creds.password)? {
-                Some(user)
-            } else {
-                None
-            })
+            let mut user = user;
+            if !user.verify_password(&creds.password)? {
+                return Ok(None);
+            }
+
+            // Upgrade hashes made with weaker parameters while we know the password.
+            // This is a cheap string comparison, so current hashes aren't slowed down.
+            if user.password_hash_is_obsolete() {
+                user.set_password(&creds.password);
+                users.update(&user)?;
+            }
+
+            Ok(Some(user))
         })
         .await?
     }
@@ -85,4 +125,41 @@ impl AuthnBackend for Backend {
 }
 
 // We use a type alias for convenience.
-pub type AuthSession = axum_login::AuthSession<Backend>;
\ No newline at end of file
+pub type AuthSession = axum_login::AuthSession<Backend>;
+
+/// Extracts the logged in user, rejecting the request with `401 Unauthorized`
+/// when there is none.
+pub struct AuthenticatedUser(pub User);
+
+impl<S: Send + Sync> FromRequestParts<S> for AuthenticatedUser {
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let auth_session = AuthSession::from_request_parts(parts, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+
+        auth_session
+            .user
+            .map(Self)
+            .ok_or_else(|| StatusCode::UNAUTHORIZED.into_response())
+    }
+}
+
+/// Extracts the logged in user if they're an admin, rejecting the request with
+/// `401 Unauthorized` when there is no user and `403 Forbidden` when they aren't one.
+pub struct AdminUser(pub User);
+
+impl<S: Send + Sync> FromRequestParts<S> for AdminUser {
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let AuthenticatedUser(user) = AuthenticatedUser::from_request_parts(parts, state).await?;
+
+        if user.is_admin {
+            Ok(Self(user))
+        } else {
+            Err(StatusCode::FORBIDDEN.into_response())
+        }
+    }
+}